@@ -1,119 +1,243 @@
 use super::ColorSpace;
 use super::Error;
-use parser::ast::{
-  Color, ColorSet, Declaration, Document, DocumentItem, RuleSet, RuleSetItem, Value, Variable,
-};
-use parser::{ResolvedColorSet, ResolvedVariable, VarContext};
+use file_tree::{Change, FileSystem, FileTree, OsFileSystem};
+use parser::ast::{Color, Document};
+use parser::color_space;
+use parser::resolved::{Declaration, Document as ResolvedDocument, Item, RuleSet, Value};
+use parser::AssetNaming;
 use serde_json::json;
-use std::fs;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::Path;
 
-struct Config<'a> {
+struct Config {
   color_space: ColorSpace,
-  var_lookup: VarContext<'a>,
+  convert_colors: bool,
+  naming: AssetNaming,
+  asset_names: RefCell<HashMap<String, String>>,
 }
 
-impl<'a> Config<'a> {
-  fn resolve_variable(&self, variable: &Variable) -> Result<ResolvedVariable, Error> {
-    self.var_lookup.resolve(variable).map_err(|err| err.into())
-  }
+/// Describes how the colors of a document are written into an asset catalog.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Options {
+  pub color_space: ColorSpace,
+  /// Determines the names of the colors. With `AssetNaming::Namespaced` ruleset folders provide a
+  /// namespace and colorsets are named after their declaration only.
+  pub naming: AssetNaming,
+  /// Converts the sRGB colors of the stylesheet into the components of `color_space`. Colors
+  /// which have been mapped into sRGB are converted into Display P3 from their original
+  /// components. Otherwise the sRGB components are written as they are and only labeled with
+  /// `color_space`, which changes the appearance of the colors in Display P3 and extended linear
+  /// sRGB.
+  pub convert_colors: bool,
+}
 
-  fn resolve_colorset(&self, colorset: &ColorSet) -> Result<ResolvedColorSet, Error> {
-    self
-      .var_lookup
-      .resolve_colorset(colorset)
-      .map_err(|err| err.into())
-  }
+/// Renders the colors of `doc` into the files of an asset catalog without touching the file
+/// system. The paths of the returned tree are relative to the catalog.
+pub fn render_asset_catalog(doc: &Document, options: &Options) -> Result<FileTree, Error> {
+  let doc = ResolvedDocument::derive_from(doc)?;
+  let config = Config {
+    color_space: options.color_space.clone(),
+    convert_colors: options.convert_colors,
+    naming: options.naming,
+    asset_names: RefCell::new(HashMap::new()),
+  };
+  let mut tree = FileTree::new();
+
+  render_items(&doc.items, Path::new(""), &config, &mut tree)?;
+
+  Ok(tree)
 }
 
+/// Writes the colors of `doc` into a new asset catalog at `path`, rendered according to `options`.
+///
+/// The catalog is rendered in memory and swapped in atomically, so `path` remains untouched if an
+/// error occurs. Returns the changes made to the files below `path`. If `dry_run` is set, the
+/// changes are only computed and nothing is written.
 pub fn write_asset_catalog(
   doc: &Document,
   path: impl AsRef<Path>,
-  color_space: ColorSpace,
+  options: &Options,
   delete_directory_if_exists: bool,
-) -> Result<(), Error> {
+  dry_run: bool,
+) -> Result<Vec<Change>, Error> {
+  write_asset_catalog_in(
+    &OsFileSystem,
+    doc,
+    path,
+    options,
+    delete_directory_if_exists,
+    dry_run,
+  )
+}
+
+/// Like `write_asset_catalog`, but writes the asset catalog into `fs`.
+pub fn write_asset_catalog_in(
+  fs: &dyn FileSystem,
+  doc: &Document,
+  path: impl AsRef<Path>,
+  options: &Options,
+  delete_directory_if_exists: bool,
+  dry_run: bool,
+) -> Result<Vec<Change>, Error> {
   let path = path.as_ref();
 
-  let config = Config {
-    color_space,
-    var_lookup: VarContext::derive_from(doc),
+  if fs.exists(path) && !delete_directory_if_exists {
+    return Err(Error::CatalogExists { path: path.into() });
+  }
+
+  let tree = render_asset_catalog(doc, options)?;
+  commit(fs, &tree, path, None, dry_run)
+}
+
+/// Writes the colors of `doc` into a group folder inside an existing asset catalog
+/// (e.g. `Assets.xcassets/GeneratedColors`).
+///
+/// Unlike `write_asset_catalog` only the group folder is owned by the generator. It is replaced
+/// on every run while sibling assets of the enclosing catalog are left untouched. See
+/// `write_asset_catalog` for the meaning of `options` and `dry_run`.
+pub fn write_asset_catalog_group(
+  doc: &Document,
+  path: impl AsRef<Path>,
+  options: &Options,
+  provides_namespace: bool,
+  dry_run: bool,
+) -> Result<Vec<Change>, Error> {
+  write_asset_catalog_group_in(
+    &OsFileSystem,
+    doc,
+    path,
+    options,
+    provides_namespace,
+    dry_run,
+  )
+}
+
+/// Like `write_asset_catalog_group`, but writes the group folder into `fs`.
+pub fn write_asset_catalog_group_in(
+  fs: &dyn FileSystem,
+  doc: &Document,
+  path: impl AsRef<Path>,
+  options: &Options,
+  provides_namespace: bool,
+  dry_run: bool,
+) -> Result<Vec<Change>, Error> {
+  let path = path.as_ref();
+
+  // Temporary files are kept next to the outermost catalog rather than inside of it, where Xcode
+  // would pick them up.
+  let Some(catalog) = path
+    .ancestors()
+    .skip(1)
+    .filter(|ancestor| is_asset_catalog(fs, ancestor))
+    .last()
+  else {
+    return Err(Error::NotInCatalog { path: path.into() });
   };
 
-  if path.exists() {
-    if delete_directory_if_exists {
-      fs::remove_dir_all(&path)?;
-    } else {
-      return Err(Error::CatalogExists { path: path.into() });
-    }
-  }
-  fs::create_dir_all(&path)?;
+  let mut tree = render_asset_catalog(doc, options)?;
+  tree.insert("Contents.json", folder_contents(provides_namespace));
+  commit(fs, &tree, path, catalog.parent(), dry_run)
+}
 
-  for item in doc.items.iter() {
-    match item {
-      DocumentItem::RuleSet(r) => {
-        write_ruleset(r, &path, &r.identifier, &config)?;
-      }
-      DocumentItem::Declaration(d) => {
-        write_declaration(d, &path, &d.identifier, &config)?;
-      }
-      DocumentItem::Variable(_) => {}
+/// Writes `tree` to `path` unless `dry_run` is set. Temporary files are placed in `scratch` if
+/// given, next to `path` otherwise.
+fn commit(
+  fs: &dyn FileSystem,
+  tree: &FileTree,
+  path: &Path,
+  scratch: Option<&Path>,
+  dry_run: bool,
+) -> Result<Vec<Change>, Error> {
+  let changes = tree.changes_in(fs, path)?;
+
+  if !dry_run {
+    match scratch {
+      Some(scratch) => fs.write_tree_via(path, scratch, tree)?,
+      None => fs.write_tree(path, tree)?,
     }
   }
 
-  Ok(())
+  Ok(changes)
 }
 
-fn write_ruleset(
-  ruleset: &RuleSet,
-  path: impl AsRef<Path>,
-  identifier: &str,
-  config: &Config,
-) -> Result<(), Error> {
-  let ruleset_path = path.as_ref().join(&ruleset.identifier);
-  fs::create_dir(&ruleset_path)?;
+fn is_asset_catalog(fs: &dyn FileSystem, path: &Path) -> bool {
+  fs.is_dir(path) && path.extension().is_some_and(|ext| ext == "xcassets")
+}
 
-  let info = json!({
+fn folder_contents(provides_namespace: bool) -> Vec<u8> {
+  let mut info = json!({
     "info": {
       "version": 1,
       "author": "xcode"
     }
   });
 
-  let json_path = ruleset_path.join("Contents.json");
+  if provides_namespace {
+    info["properties"] = json!({ "provides-namespace": true });
+  }
 
-  fs::write(
-    &json_path,
-    serde_json::to_string_pretty(&info).unwrap().as_bytes(),
-  )?;
+  serde_json::to_string_pretty(&info).unwrap().into_bytes()
+}
 
-  for item in ruleset.items.iter() {
+fn render_items(
+  items: &[Item],
+  path: &Path,
+  config: &Config,
+  tree: &mut FileTree,
+) -> Result<(), Error> {
+  for item in items {
     match item {
-      RuleSetItem::RuleSet(r) => {
-        let child_identifier = format!("{}{}", identifier, r.identifier);
-        write_ruleset(r, &ruleset_path, &child_identifier, &config)?;
-      }
-      RuleSetItem::Declaration(d) => {
-        let child_identifier = format!("{}{}", identifier, d.identifier);
-        write_declaration(d, &ruleset_path, &child_identifier, &config)?;
-      }
+      Item::RuleSet(r) => render_ruleset(r, path, config, tree)?,
+      Item::Declaration(d) => render_declaration(d, path, config, tree)?,
     }
   }
 
   Ok(())
 }
 
-fn write_declaration(
-  declaration: &Declaration<Value>,
-  path: impl AsRef<Path>,
-  identifier: &str,
+fn render_ruleset(
+  ruleset: &RuleSet,
+  path: &Path,
   config: &Config,
+  tree: &mut FileTree,
 ) -> Result<(), Error> {
-  let colorset_path = path
-    .as_ref()
-    .join(identifier.to_string())
-    .with_extension("colorset");
+  let ruleset_path = path.join(ruleset.identifier());
+
+  tree.insert(
+    ruleset_path.join("Contents.json"),
+    folder_contents(config.naming.provides_namespace()),
+  );
 
-  fs::create_dir(&colorset_path)?;
+  render_items(&ruleset.items, &ruleset_path, config, tree)
+}
+
+fn render_declaration(
+  declaration: &Declaration,
+  path: &Path,
+  config: &Config,
+  tree: &mut FileTree,
+) -> Result<(), Error> {
+  let asset_name = declaration.asset_name(config.naming);
+  let source_path = declaration.dotted_path();
+  let colorset_name = if config.naming.provides_namespace() {
+    declaration.identifier()
+  } else {
+    &asset_name
+  };
+  let colorset_path = path.join(format!("{}.colorset", colorset_name));
+
+  if let Some(existing_path) = config
+    .asset_names
+    .borrow_mut()
+    .insert(asset_name.clone(), source_path.clone())
+  {
+    return Err(Error::DuplicateAssetName {
+      name: asset_name,
+      first: existing_path,
+      second: source_path,
+    });
+  }
 
   let mut info: serde_json::value::Value = json!({
     "info": {
@@ -124,68 +248,58 @@ fn write_declaration(
   });
 
   let components = |color: &Color| {
-    json!({
-      "red": format!("0x{:02X}", color.r),
-      "green": format!("0x{:02X}", color.g),
-      "blue": format!("0x{:02X}", color.b),
-      "alpha": format!("{:.3}", color.a)
-    })
-  };
-
-  let append_light_color =
-    |value: &mut serde_json::value::Value, color: &Color| -> Result<(), Error> {
-      let arr = value["colors"].as_array_mut().unwrap();
-      arr.push(json!({
-        "idiom": "universal",
-        "color": {
-          "color-space" : config.color_space.to_string(),
-          "components": components(color)
-        }
-      }));
-      Ok(())
+    let converted = match config.color_space {
+      _ if !config.convert_colors => None,
+      ColorSpace::DisplayP3 => Some(color_space::display_p3_components(color)),
+      ColorSpace::ExtendedRangeLinearSRGB => Some(color_space::linear_srgb_components(color)),
+      ColorSpace::SRGB | ColorSpace::ExtendedRangeSRGB => None,
     };
 
-  let append_dark_color =
-    |value: &mut serde_json::value::Value, color: &Color| -> Result<(), Error> {
-      let arr = value["colors"].as_array_mut().unwrap();
-      arr.push(json!({
-        "idiom" : "universal",
-        "appearances" : [{
-          "appearance" : "luminosity",
-          "value" : "dark"
-        }],
-        "color" : {
-          "color-space" : config.color_space.to_string(),
-          "components" : components(color)
-        }
-      }));
-      Ok(())
-    };
+    match converted {
+      Some([red, green, blue]) => json!({
+        "red": format!("{:.4}", red),
+        "green": format!("{:.4}", green),
+        "blue": format!("{:.4}", blue),
+        "alpha": format!("{:.3}", color.a)
+      }),
+      None => json!({
+        "red": format!("0x{:02X}", color.r),
+        "green": format!("0x{:02X}", color.g),
+        "blue": format!("0x{:02X}", color.b),
+        "alpha": format!("{:.3}", color.a)
+      }),
+    }
+  };
 
-  let append_colorset =
-    |value: &mut serde_json::value::Value, colorset: &ResolvedColorSet| -> Result<(), Error> {
-      append_light_color(value, &colorset.light)?;
-      append_dark_color(value, &colorset.dark)?;
-      Ok(())
-    };
+  let append_color = |value: &mut serde_json::value::Value, color: &Color, dark: bool| {
+    let mut entry = json!({
+      "idiom": "universal",
+      "color": {
+        "color-space" : config.color_space.to_string(),
+        "components": components(color)
+      }
+    });
+    if dark {
+      entry["appearances"] = json!([{
+        "appearance" : "luminosity",
+        "value" : "dark"
+      }]);
+    }
+    value["colors"].as_array_mut().unwrap().push(entry);
+  };
 
-  match declaration.value {
-    Value::Variable(ref identifier) => match config.resolve_variable(identifier)? {
-      ResolvedVariable::Color(color) => append_light_color(&mut info, &color)?,
-      ResolvedVariable::ColorSet(colorset) => append_colorset(&mut info, &colorset)?,
-    },
-    Value::Color(ref color) => append_light_color(&mut info, color)?,
-    Value::ColorSet(ref colorset) => {
-      append_colorset(&mut info, &config.resolve_colorset(colorset)?)?
+  match &declaration.value {
+    Value::Color(color) => append_color(&mut info, color, false),
+    Value::ColorSet(colorset) => {
+      append_color(&mut info, &colorset.light.color, false);
+      append_color(&mut info, &colorset.dark.color, true);
     }
   }
 
-  let json_path = colorset_path.join("Contents.json");
-
-  fs::write(
-    &json_path,
-    serde_json::to_string_pretty(&info).unwrap().as_bytes(),
-  )?;
+  tree.insert(
+    colorset_path.join("Contents.json"),
+    serde_json::to_string_pretty(&info).unwrap(),
+  );
 
   Ok(())
 }
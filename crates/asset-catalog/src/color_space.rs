@@ -1,9 +1,10 @@
+use std::fmt;
 use std::str::FromStr;
-use std::string::ToString;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum ColorSpace {
   DisplayP3,
+  #[default]
   SRGB,
   ExtendedRangeSRGB,
   ExtendedRangeLinearSRGB,
@@ -23,13 +24,13 @@ impl FromStr for ColorSpace {
   }
 }
 
-impl ToString for ColorSpace {
-  fn to_string(&self) -> String {
-    match self {
-      Self::DisplayP3 => "display-p3".to_string(),
-      Self::SRGB => "srgb".to_string(),
-      Self::ExtendedRangeSRGB => "extended-srgb".to_string(),
-      Self::ExtendedRangeLinearSRGB => "extended-linear-srgb".to_string(),
-    }
+impl fmt::Display for ColorSpace {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::DisplayP3 => "display-p3",
+      Self::SRGB => "srgb",
+      Self::ExtendedRangeSRGB => "extended-srgb",
+      Self::ExtendedRangeLinearSRGB => "extended-linear-srgb",
+    })
   }
 }
@@ -5,6 +5,14 @@ use std::path::PathBuf;
 pub enum Error {
   #[error("Asset Catalog already exists at path {path:?}")]
   CatalogExists { path: PathBuf },
+  #[error("{path:?} is not located inside an Asset Catalog")]
+  NotInCatalog { path: PathBuf },
+  #[error("{first} and {second} would both be written as color {name}")]
+  DuplicateAssetName {
+    name: String,
+    first: String,
+    second: String,
+  },
   #[error(transparent)]
   Io(#[from] io::Error),
   #[error(transparent)]
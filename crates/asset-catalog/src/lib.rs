@@ -2,6 +2,11 @@ mod asset_catalog;
 mod color_space;
 mod error;
 
-pub use self::asset_catalog::write_asset_catalog;
+pub use self::asset_catalog::{
+  render_asset_catalog, write_asset_catalog, write_asset_catalog_group,
+  write_asset_catalog_group_in, write_asset_catalog_in, Options,
+};
 pub use self::error::Error;
 pub use color_space::ColorSpace;
+pub use file_tree::{Change, FileSystem, FileTree, MemoryFileSystem, OsFileSystem};
+pub use parser::AssetNaming;
@@ -1,5 +1,11 @@
-use asset_catalog::{write_asset_catalog, ColorSpace};
+use asset_catalog::{
+  render_asset_catalog, write_asset_catalog, write_asset_catalog_group,
+  write_asset_catalog_group_in, write_asset_catalog_in, AssetNaming, Change, ColorSpace, Error,
+  MemoryFileSystem, Options, OsFileSystem,
+};
 use parser::{ast::Document, parse_document};
+use std::fs;
+use std::path::Path;
 use tempdir::TempDir;
 
 #[test]
@@ -7,12 +13,17 @@ fn srgb_asset_catalog() {
   let tmp_dir_srgb = TempDir::new("asset_catalog_srgb").expect("Create temp dir failed");
   write_asset_catalog(
     &test_document(),
-    &tmp_dir_srgb.path(),
-    ColorSpace::SRGB,
+    tmp_dir_srgb.path(),
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
     true,
+    false,
   )
   .expect("Could not write asset catalog");
-  assert!(!dir_diff::is_different(&tmp_dir_srgb.path(), "tests/fixtures/SRGB.xcassets").unwrap());
+  assert!(!dir_diff::is_different(tmp_dir_srgb.path(), "tests/fixtures/SRGB.xcassets").unwrap());
 }
 
 #[test]
@@ -21,31 +32,98 @@ fn display_p3_asset_catalog() {
     TempDir::new("asset_catalog_display_p3").expect("Create temp dir failed");
   write_asset_catalog(
     &test_document(),
-    &tmp_dir_display_p3.path(),
-    ColorSpace::DisplayP3,
+    tmp_dir_display_p3.path(),
+    &Options {
+      color_space: ColorSpace::DisplayP3,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
     true,
+    false,
   )
   .expect("Could not write asset catalog");
   assert!(!dir_diff::is_different(
-    &tmp_dir_display_p3.path(),
+    tmp_dir_display_p3.path(),
     "tests/fixtures/DisplayP3.xcassets"
   )
   .unwrap());
 }
 
+#[test]
+fn converted_asset_catalogs() {
+  for (color_space, fixture) in [
+    (ColorSpace::DisplayP3, "ConvertedDisplayP3.xcassets"),
+    (
+      ColorSpace::ExtendedRangeLinearSRGB,
+      "ConvertedExtendedRangeLinearSRGB.xcassets",
+    ),
+  ] {
+    let tmp_dir = TempDir::new("asset_catalog_converted").expect("Create temp dir failed");
+    write_asset_catalog_in(
+      &OsFileSystem,
+      &test_document(),
+      tmp_dir.path(),
+      &Options {
+        color_space,
+        naming: AssetNaming::Concatenated,
+        convert_colors: true,
+      },
+      true,
+      false,
+    )
+    .expect("Could not write asset catalog");
+    assert!(
+      !dir_diff::is_different(tmp_dir.path(), Path::new("tests/fixtures").join(fixture)).unwrap()
+    );
+  }
+}
+
+#[test]
+fn wide_gamut_colors_converted_into_display_p3() {
+  let doc =
+    parse_document("Accent: oklch(65% 0.26 145)".to_string()).expect("Could not parse document");
+  let tree = render_asset_catalog(
+    &doc,
+    &Options {
+      color_space: ColorSpace::DisplayP3,
+      naming: AssetNaming::Concatenated,
+      convert_colors: true,
+    },
+  )
+  .expect("Could not render asset catalog");
+
+  // The components of the original color, not those of the color mapped into sRGB.
+  let contents: serde_json::Value =
+    serde_json::from_slice(tree.get("Accent.colorset/Contents.json").unwrap()).unwrap();
+  assert_eq!(
+    contents["colors"][0]["color"]["components"],
+    serde_json::json!({
+      "red": "0.1696",
+      "green": "0.6910",
+      "blue": "0.1140",
+      "alpha": "1.000"
+    })
+  );
+}
+
 #[test]
 fn extended_linear_srgb_asset_catalog() {
   let tmp_dir_extended_linear_srgb =
     TempDir::new("asset_catalog_extended_linear_srgb").expect("Create temp dir failed");
   write_asset_catalog(
     &test_document(),
-    &tmp_dir_extended_linear_srgb.path(),
-    ColorSpace::ExtendedRangeLinearSRGB,
+    tmp_dir_extended_linear_srgb.path(),
+    &Options {
+      color_space: ColorSpace::ExtendedRangeLinearSRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
     true,
+    false,
   )
   .expect("Could not write asset catalog");
   assert!(!dir_diff::is_different(
-    &tmp_dir_extended_linear_srgb.path(),
+    tmp_dir_extended_linear_srgb.path(),
     "tests/fixtures/ExtendedRangeLinearSRGB.xcassets"
   )
   .unwrap());
@@ -57,18 +135,400 @@ fn extended_srgb_asset_catalog() {
     TempDir::new("asset_catalog_extended_srgb").expect("Create temp dir failed");
   write_asset_catalog(
     &test_document(),
-    &tmp_dir_extended_srgb.path(),
-    ColorSpace::ExtendedRangeSRGB,
+    tmp_dir_extended_srgb.path(),
+    &Options {
+      color_space: ColorSpace::ExtendedRangeSRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
     true,
+    false,
   )
   .expect("Could not write asset catalog");
   assert!(!dir_diff::is_different(
-    &tmp_dir_extended_srgb.path(),
+    tmp_dir_extended_srgb.path(),
     "tests/fixtures/ExtendedRangeSRGB.xcassets"
   )
   .unwrap());
 }
 
+#[test]
+fn namespaced_asset_catalog() {
+  let tmp_dir_namespaced =
+    TempDir::new("asset_catalog_namespaced").expect("Create temp dir failed");
+  write_asset_catalog(
+    &test_document(),
+    tmp_dir_namespaced.path(),
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Namespaced,
+      ..Options::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog");
+  assert!(!dir_diff::is_different(
+    tmp_dir_namespaced.path(),
+    "tests/fixtures/SRGBNamespaced.xcassets"
+  )
+  .unwrap());
+}
+
+#[test]
+fn dotted_asset_catalog() {
+  let tmp_dir = TempDir::new("asset_catalog_dotted").expect("Create temp dir failed");
+  let doc =
+    parse_document("A {\n B {\n C: #ffffff\n }\n }".to_string()).expect("Could not parse document");
+
+  let changes = write_asset_catalog(
+    &doc,
+    tmp_dir.path(),
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Dotted,
+      ..Options::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog");
+
+  let paths: Vec<&Path> = changes.iter().map(|change| change.path()).collect();
+  assert_eq!(
+    paths,
+    vec![
+      Path::new("A/B/A.B.C.colorset/Contents.json"),
+      Path::new("A/B/Contents.json"),
+      Path::new("A/Contents.json"),
+    ]
+  );
+}
+
+#[test]
+fn deprecated_declarations() {
+  let tmp_dir = TempDir::new("asset_catalog_deprecated").expect("Create temp dir failed");
+  let doc = parse_document(
+    "@renamed(Body)\nPrimary: #151618\n@deprecated\nSecondary: #85868A\nBody: #151618".to_string(),
+  )
+  .expect("Could not parse document");
+
+  let changes = write_asset_catalog(
+    &doc,
+    tmp_dir.path(),
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog");
+
+  let paths: Vec<&Path> = changes.iter().map(|change| change.path()).collect();
+  assert_eq!(
+    paths,
+    vec![
+      Path::new("Body.colorset/Contents.json"),
+      Path::new("Primary.colorset/Contents.json"),
+      Path::new("Secondary.colorset/Contents.json"),
+    ]
+  );
+}
+
+#[test]
+fn duplicate_asset_names() {
+  let tmp_dir = TempDir::new("asset_catalog_duplicates").expect("Create temp dir failed");
+  let doc = parse_document("AB {\n C: #ffffff\n }\nA {\n BC: #000000\n }".to_string())
+    .expect("Could not parse document");
+
+  match write_asset_catalog(
+    &doc,
+    tmp_dir.path(),
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
+    true,
+    false,
+  ) {
+    Err(Error::DuplicateAssetName {
+      name,
+      first,
+      second,
+    }) => {
+      assert_eq!(name, "ABC");
+      assert_eq!(first, "AB.C");
+      assert_eq!(second, "A.BC");
+    }
+    other => panic!("Expected DuplicateAssetName error, got {:?}", other),
+  }
+
+  write_asset_catalog(
+    &doc,
+    tmp_dir.path(),
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Namespaced,
+      ..Options::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog");
+}
+
+#[test]
+fn asset_catalog_group() {
+  let tmp_dir = TempDir::new("asset_catalog_group").expect("Create temp dir failed");
+  let catalog_path = tmp_dir.path().join("Assets.xcassets");
+  let sibling_path = catalog_path.join("AppIcon.appiconset");
+  let group_path = catalog_path.join("GeneratedColors");
+
+  fs::create_dir_all(&sibling_path).unwrap();
+  fs::write(sibling_path.join("Contents.json"), "{}").unwrap();
+  fs::create_dir_all(group_path.join("Stale.colorset")).unwrap();
+
+  write_asset_catalog_group(
+    &test_document(),
+    &group_path,
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog group");
+
+  assert_eq!(
+    fs::read_to_string(sibling_path.join("Contents.json")).unwrap(),
+    "{}"
+  );
+  assert!(!group_path.join("Stale.colorset").exists());
+  // Nothing but the group has been added to the catalog and no temporary files are left behind.
+  assert_eq!(fs::read_dir(&catalog_path).unwrap().count(), 2);
+  assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+  assert_eq!(
+    fs::read_to_string(group_path.join("Contents.json")).unwrap(),
+    r#"{
+  "info": {
+    "author": "xcode",
+    "version": 1
+  },
+  "properties": {
+    "provides-namespace": true
+  }
+}"#
+  );
+
+  for entry in fs::read_dir("tests/fixtures/SRGB.xcassets").unwrap() {
+    let entry = entry.unwrap();
+    assert!(!dir_diff::is_different(entry.path(), group_path.join(entry.file_name())).unwrap());
+  }
+}
+
+#[test]
+fn asset_catalog_group_outside_of_catalog() {
+  let tmp_dir = TempDir::new("asset_catalog_group_outside").expect("Create temp dir failed");
+
+  match write_asset_catalog_group(
+    &test_document(),
+    tmp_dir.path().join("GeneratedColors"),
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
+    false,
+    false,
+  ) {
+    Err(Error::NotInCatalog { .. }) => {}
+    other => panic!("Expected NotInCatalog error, got {:?}", other),
+  }
+}
+
+#[test]
+fn render_asset_catalog_in_memory() {
+  let tree = render_asset_catalog(&test_document(), &Options::default())
+    .expect("Could not render asset catalog");
+
+  let mut fixture_count = 0;
+  for entry in walk_files(Path::new("tests/fixtures/SRGB.xcassets")) {
+    let relative_path = entry.strip_prefix("tests/fixtures/SRGB.xcassets").unwrap();
+    assert_eq!(
+      tree.get(relative_path),
+      Some(fs::read(&entry).unwrap().as_slice()),
+      "{:?} differs",
+      relative_path
+    );
+    fixture_count += 1;
+  }
+  assert_eq!(tree.len(), fixture_count);
+}
+
+#[test]
+fn write_asset_catalog_into_memory_file_system() {
+  let fs = MemoryFileSystem::new();
+  fs.insert("Stale.xcassets/Stale.colorset/Contents.json", "{}");
+
+  match write_asset_catalog_in(
+    &fs,
+    &test_document(),
+    "Stale.xcassets",
+    &Options::default(),
+    false,
+    false,
+  ) {
+    Err(Error::CatalogExists { .. }) => {}
+    other => panic!("Expected CatalogExists error, got {:?}", other),
+  }
+
+  let changes = write_asset_catalog_in(
+    &fs,
+    &test_document(),
+    "Stale.xcassets",
+    &Options::default(),
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog");
+
+  let tree = render_asset_catalog(&test_document(), &Options::default()).unwrap();
+  assert_eq!(changes.len(), tree.len() + 1);
+  assert!(fs
+    .get("Stale.xcassets/Stale.colorset/Contents.json")
+    .is_none());
+  assert_eq!(fs.paths().len(), tree.len());
+  for (path, contents) in tree.iter() {
+    assert_eq!(
+      fs.get(Path::new("Stale.xcassets").join(path)),
+      Some(contents.to_vec())
+    );
+  }
+}
+
+#[test]
+fn write_asset_catalog_group_into_memory_file_system() {
+  let fs = MemoryFileSystem::new();
+
+  match write_asset_catalog_group_in(
+    &fs,
+    &test_document(),
+    "Assets.xcassets/Colors",
+    &Options::default(),
+    false,
+    false,
+  ) {
+    Err(Error::NotInCatalog { .. }) => {}
+    other => panic!("Expected NotInCatalog error, got {:?}", other),
+  }
+
+  fs.insert("Assets.xcassets/Contents.json", "{}");
+  write_asset_catalog_group_in(
+    &fs,
+    &test_document(),
+    "Assets.xcassets/Colors",
+    &Options::default(),
+    false,
+    false,
+  )
+  .expect("Could not write asset catalog group");
+
+  assert_eq!(
+    fs.get("Assets.xcassets/Contents.json"),
+    Some(b"{}".to_vec())
+  );
+  assert!(fs.get("Assets.xcassets/Colors/Contents.json").is_some());
+}
+
+#[test]
+fn dry_run_asset_catalog() {
+  let tmp_dir = TempDir::new("asset_catalog_dry_run").expect("Create temp dir failed");
+  let catalog_path = tmp_dir.path().join("Colors.xcassets");
+  let stale_path = catalog_path.join("Stale.colorset/Contents.json");
+
+  write_asset_catalog(
+    &test_document(),
+    &catalog_path,
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog");
+  fs::create_dir_all(stale_path.parent().unwrap()).unwrap();
+  fs::write(&stale_path, "{}").unwrap();
+
+  let changes = write_asset_catalog(
+    &test_document(),
+    &catalog_path,
+    &Options {
+      color_space: ColorSpace::DisplayP3,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
+    true,
+    true,
+  )
+  .expect("Could not compute changes");
+
+  assert_eq!(changes.len(), 13);
+  for change in changes {
+    if change.path() == Path::new("Stale.colorset/Contents.json") {
+      assert!(matches!(change, Change::Deleted { .. }));
+    } else {
+      assert!(matches!(change, Change::Changed { .. }));
+    }
+  }
+  assert!(stale_path.exists());
+  assert!(!dir_diff::is_different(
+    catalog_path.join("Text"),
+    "tests/fixtures/SRGB.xcassets/Text"
+  )
+  .unwrap());
+}
+
+#[test]
+fn failed_rendering_leaves_asset_catalog_untouched() {
+  let tmp_dir = TempDir::new("asset_catalog_failure").expect("Create temp dir failed");
+  let catalog_path = tmp_dir.path().join("Colors.xcassets");
+
+  write_asset_catalog(
+    &test_document(),
+    &catalog_path,
+    &Options {
+      color_space: ColorSpace::SRGB,
+      naming: AssetNaming::Concatenated,
+      ..Options::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write asset catalog");
+
+  let doc = parse_document(
+    r#"
+    A: #ffffff
+    B {
+      C: $missing
+    }
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+
+  assert!(write_asset_catalog(&doc, &catalog_path, &Options::default(), true, false).is_err());
+  assert!(!dir_diff::is_different(&catalog_path, "tests/fixtures/SRGB.xcassets").unwrap());
+  assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+}
+
 fn test_document() -> Document {
   let contents = r#"
     // Basic colors
@@ -114,3 +574,16 @@ fn test_document() -> Document {
 
   parse_document(contents.to_string()).expect("Could not parse document")
 }
+
+fn walk_files(path: &Path) -> Vec<std::path::PathBuf> {
+  let mut files = vec![];
+  for entry in fs::read_dir(path).unwrap() {
+    let path = entry.unwrap().path();
+    if path.is_dir() {
+      files.extend(walk_files(&path));
+    } else {
+      files.push(path);
+    }
+  }
+  files
+}
@@ -0,0 +1,303 @@
+use super::Error;
+use parser::ast::{Color, Declaration as ASTDeclaration, Document, DocumentItem, RuleSetItem};
+use parser::ast::{Value as ASTValue, Variable};
+use parser::color_space::{color_difference, is_same_color};
+use parser::resolved::{Declaration as ResolvedDeclaration, Document as ResolvedDocument, Value};
+use parser::VarContext;
+use std::collections::{HashMap, HashSet};
+
+/// The differences between the resolved declarations of two stylesheets.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Diff {
+  /// Declarations which only exist in the new stylesheet, in its order.
+  pub added: Vec<Declaration>,
+  /// Declarations which only exist in the old stylesheet, in its order.
+  pub removed: Vec<Declaration>,
+  /// Declarations which have moved to another path without changing their colors.
+  pub renamed: Vec<Rename>,
+  /// Declarations which exist in both stylesheets with different colors, in the order of the new
+  /// stylesheet.
+  pub changed: Vec<Modification>,
+}
+
+/// A declaration which exists in only one of the stylesheets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+  /// The dot-separated path of the declaration, e.g. `Text.Primary`.
+  pub path: String,
+  pub light: Color,
+  pub dark: Color,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rename {
+  pub old_path: String,
+  pub new_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modification {
+  pub path: String,
+  /// The change of the light color, if any.
+  pub light: Option<ColorChange>,
+  /// The change of the dark color, if any.
+  pub dark: Option<ColorChange>,
+  /// The variables whose change caused all changes of the declaration, which itself still refers
+  /// to the same variables. Aliases are followed to the variable whose value has been edited, e.g.
+  /// `brand` rather than `ink` for `Primary: $ink` and `$ink: $brand`. Empty if the declaration
+  /// has been edited.
+  pub variables: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorChange {
+  pub old: Color,
+  pub new: Color,
+  /// The CIE76 color difference between both colors, ignoring their alpha.
+  pub delta_e: f32,
+}
+
+impl Diff {
+  /// Resolves the variables of both stylesheets and compares their declarations by path.
+  pub fn between(old: &Document, new: &Document) -> Result<Self, Error> {
+    let sources = Sources {
+      old: parser::expand_extends(&parser::expand_scales(old))?,
+      new: parser::expand_extends(&parser::expand_scales(new))?,
+    };
+    let old = ResolvedDocument::derive_from(old)?;
+    let new = ResolvedDocument::derive_from(new)?;
+    let old_declarations = old.declarations();
+    let new_declarations = new.declarations();
+    let old_by_path: HashMap<String, &ResolvedDeclaration> = old_declarations
+      .iter()
+      .map(|decl| (decl.dotted_path(), *decl))
+      .collect();
+    let new_paths: HashSet<String> = new_declarations
+      .iter()
+      .map(|decl| decl.dotted_path())
+      .collect();
+
+    let mut diff = Diff::default();
+    let mut added = vec![];
+
+    for decl in &new_declarations {
+      match old_by_path.get(&decl.dotted_path()) {
+        Some(old_decl) => diff
+          .changed
+          .extend(Modification::between(old_decl, decl, &sources)),
+        None => added.push(*decl),
+      }
+    }
+
+    let mut removed: Vec<&ResolvedDeclaration> = old_declarations
+      .into_iter()
+      .filter(|decl| !new_paths.contains(&decl.dotted_path()))
+      .collect();
+
+    // A removed and an added declaration with the same colors are considered a rename, unless
+    // other removed or added declarations share these colors.
+    for decl in &added {
+      let removed_matches = removed
+        .iter()
+        .filter(|other| has_same_colors(other, decl))
+        .count();
+      let added_matches = added
+        .iter()
+        .filter(|other| has_same_colors(other, decl))
+        .count();
+
+      match removed
+        .iter()
+        .position(|other| has_same_colors(other, decl))
+      {
+        Some(index) if removed_matches == 1 && added_matches == 1 => {
+          let old_decl = removed.remove(index);
+          diff.renamed.push(Rename {
+            old_path: old_decl.dotted_path(),
+            new_path: decl.dotted_path(),
+          });
+        }
+        _ => diff.added.push(Declaration::from(*decl)),
+      }
+    }
+    diff.removed = removed.into_iter().map(Declaration::from).collect();
+
+    Ok(diff)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty()
+      && self.removed.is_empty()
+      && self.renamed.is_empty()
+      && self.changed.is_empty()
+  }
+}
+
+impl Modification {
+  fn between(
+    old: &ResolvedDeclaration,
+    new: &ResolvedDeclaration,
+    sources: &Sources,
+  ) -> Option<Self> {
+    let (old_light, old_dark) = old.appearances();
+    let (new_light, new_dark) = new.appearances();
+    let light = ColorChange::between(old_light, new_light);
+    let dark = ColorChange::between(old_dark, new_dark);
+
+    if light.is_none() && dark.is_none() {
+      return None;
+    }
+
+    let (old_variables, new_variables) = (variables(old), variables(new));
+    let mut changed_variables = vec![];
+    let mut is_indirect = true;
+
+    for (change, old_variable, new_variable, dark) in [
+      (&light, &old_variables.0, &new_variables.0, false),
+      (&dark, &old_variables.1, &new_variables.1, true),
+    ] {
+      if change.is_none() {
+        continue;
+      }
+      match (old_variable, new_variable) {
+        (Some(old_variable), Some(new_variable)) if old_variable == new_variable => {
+          let changed = sources.changed_variable(new, new_variable, dark);
+          if !changed_variables.contains(&changed) {
+            changed_variables.push(changed);
+          }
+        }
+        _ => is_indirect = false,
+      }
+    }
+
+    Some(Modification {
+      path: new.dotted_path(),
+      light,
+      dark,
+      variables: if is_indirect {
+        changed_variables
+      } else {
+        vec![]
+      },
+    })
+  }
+
+  /// Whether the declaration only changed because a variable it refers to changed.
+  pub fn is_indirect(&self) -> bool {
+    !self.variables.is_empty()
+  }
+}
+
+/// Both stylesheets with their scales and `@extend`s expanded, to look up variables.
+struct Sources {
+  old: Document,
+  new: Document,
+}
+
+impl Sources {
+  /// Returns the variable whose value has been edited among `variable` and the variables it is an
+  /// alias of, looked up from `decl`. Falls back to the last alias if none of them differ.
+  fn changed_variable(&self, decl: &ResolvedDeclaration, variable: &str, dark: bool) -> String {
+    let variable = Variable {
+      identifier: variable.to_string(),
+      opacity: 1.0,
+    };
+    let old_aliases = context(&self.old, &decl.path).aliases(&variable, dark);
+    let new_aliases = context(&self.new, &decl.path).aliases(&variable, dark);
+
+    new_aliases
+      .iter()
+      .zip(old_aliases.iter().map(Some).chain(std::iter::repeat(None)))
+      .find(|(new, old)| !old.is_some_and(|old| is_same_variable(old, new)))
+      .map(|(new, _)| *new)
+      .or_else(|| new_aliases.last().copied())
+      .map_or(variable.identifier, |alias| alias.identifier.clone())
+  }
+}
+
+/// Returns the context of the ruleset enclosing the declaration at `path`.
+fn context<'a>(doc: &'a Document, path: &[String]) -> VarContext<'a> {
+  let mut ctx = VarContext::derive_from(doc);
+  let mut rulesets = doc
+    .items
+    .iter()
+    .filter_map(|item| match item {
+      DocumentItem::RuleSet(ruleset) => Some(ruleset),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+
+  for identifier in &path[..path.len().saturating_sub(1)] {
+    let Some(ruleset) = rulesets
+      .into_iter()
+      .find(|ruleset| ruleset.identifier == *identifier)
+    else {
+      break;
+    };
+    ctx = ctx.scoped(ruleset);
+    rulesets = ruleset
+      .items
+      .iter()
+      .filter_map(|item| match item {
+        RuleSetItem::RuleSet(ruleset) => Some(ruleset),
+        _ => None,
+      })
+      .collect();
+  }
+
+  ctx
+}
+
+fn is_same_variable(a: &ASTDeclaration<ASTValue>, b: &ASTDeclaration<ASTValue>) -> bool {
+  a.identifier == b.identifier && a.value == b.value
+}
+
+impl ColorChange {
+  fn between(old: &Color, new: &Color) -> Option<Self> {
+    if is_same_color(old, new) {
+      return None;
+    }
+    Some(ColorChange {
+      old: old.clone(),
+      new: new.clone(),
+      delta_e: color_difference(old, new),
+    })
+  }
+}
+
+impl From<&ResolvedDeclaration> for Declaration {
+  fn from(decl: &ResolvedDeclaration) -> Self {
+    let (light, dark) = decl.appearances();
+    Declaration {
+      path: decl.dotted_path(),
+      light: light.clone(),
+      dark: dark.clone(),
+    }
+  }
+}
+
+/// The variables the light and dark color of `decl` have been taken from. Colors and colorsets
+/// assigned as a whole remember their variable on the declaration.
+fn variables(decl: &ResolvedDeclaration) -> (Option<String>, Option<String>) {
+  match &decl.value {
+    Value::Color(_) => (decl.variable.clone(), decl.variable.clone()),
+    Value::ColorSet(colorset) => (
+      colorset
+        .light
+        .variable
+        .clone()
+        .or_else(|| decl.variable.clone()),
+      colorset
+        .dark
+        .variable
+        .clone()
+        .or_else(|| decl.variable.clone()),
+    ),
+  }
+}
+
+fn has_same_colors(a: &ResolvedDeclaration, b: &ResolvedDeclaration) -> bool {
+  let (a_light, a_dark) = a.appearances();
+  let (b_light, b_dark) = b.appearances();
+  is_same_color(a_light, b_light) && is_same_color(a_dark, b_dark)
+}
@@ -0,0 +1,133 @@
+use super::diff::{ColorChange, Declaration, Diff, Modification};
+use parser::color_space::hex_string;
+use serde_json::json;
+use std::fmt;
+
+impl Diff {
+  /// Describes the diff as JSON, with colors in the notation of the stylesheet.
+  pub fn to_json(&self) -> serde_json::Value {
+    let declaration = |decl: &Declaration| {
+      json!({
+        "path": decl.path,
+        "light": hex_string(&decl.light),
+        "dark": hex_string(&decl.dark),
+      })
+    };
+    let change = |change: &Option<ColorChange>| {
+      change.as_ref().map(|change| {
+        json!({
+          "old": hex_string(&change.old),
+          "new": hex_string(&change.new),
+          "delta-e": (f64::from(change.delta_e) * 100.0).round() / 100.0,
+        })
+      })
+    };
+
+    json!({
+      "added": self.added.iter().map(declaration).collect::<Vec<_>>(),
+      "removed": self.removed.iter().map(declaration).collect::<Vec<_>>(),
+      "renamed": self.renamed.iter().map(|rename| json!({
+        "old-path": rename.old_path,
+        "new-path": rename.new_path,
+      })).collect::<Vec<_>>(),
+      "changed": self.changed.iter().map(|modification| json!({
+        "path": modification.path,
+        "light": change(&modification.light),
+        "dark": change(&modification.dark),
+        "indirect": modification.is_indirect(),
+        "variables": modification.variables,
+      })).collect::<Vec<_>>(),
+    })
+  }
+}
+
+impl fmt::Display for Diff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_empty() {
+      return writeln!(f, "No changes.");
+    }
+
+    if !self.added.is_empty() {
+      writeln!(f, "Added:")?;
+      for decl in &self.added {
+        writeln!(f, "  + {}: {}", decl.path, colors_string(decl))?;
+      }
+    }
+    if !self.removed.is_empty() {
+      writeln!(f, "Removed:")?;
+      for decl in &self.removed {
+        writeln!(f, "  - {}: {}", decl.path, colors_string(decl))?;
+      }
+    }
+    if !self.renamed.is_empty() {
+      writeln!(f, "Renamed:")?;
+      for rename in &self.renamed {
+        writeln!(f, "  {} → {}", rename.old_path, rename.new_path)?;
+      }
+    }
+
+    let (indirect, direct): (Vec<&Modification>, Vec<&Modification>) =
+      self.changed.iter().partition(|m| m.is_indirect());
+    if !direct.is_empty() {
+      writeln!(f, "Changed:")?;
+      for modification in direct {
+        write_modification(f, modification)?;
+      }
+    }
+    if !indirect.is_empty() {
+      writeln!(f, "Changed indirectly by variables:")?;
+      for modification in indirect {
+        write_modification(f, modification)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn write_modification(f: &mut fmt::Formatter<'_>, modification: &Modification) -> fmt::Result {
+  if modification.is_indirect() {
+    let variables: Vec<String> = modification
+      .variables
+      .iter()
+      .map(|variable| format!("${}", variable))
+      .collect();
+    writeln!(f, "  {} (via {})", modification.path, variables.join(", "))?;
+  } else {
+    writeln!(f, "  {}", modification.path)?;
+  }
+
+  // A single color changes in both appearances alike and is printed once, as in the stylesheet.
+  if let (Some(light), Some(dark)) = (&modification.light, &modification.dark) {
+    if change_string(light) == change_string(dark) {
+      return writeln!(f, "    {}", change_string(light));
+    }
+  }
+
+  for (appearance, change) in [("light", &modification.light), ("dark", &modification.dark)] {
+    if let Some(change) = change {
+      writeln!(f, "    {}: {}", appearance, change_string(change))?;
+    }
+  }
+  Ok(())
+}
+
+/// Formats `change` like `#151618 → #1C1C1E (ΔE 10.45)`.
+fn change_string(change: &ColorChange) -> String {
+  format!(
+    "{} → {} (ΔE {:.2})",
+    hex_string(&change.old),
+    hex_string(&change.new),
+    change.delta_e
+  )
+}
+
+/// Formats the colors of `decl` in the notation of the stylesheet, e.g. `#151618` or
+/// `(light: #151618, dark: #E7E8EA)`.
+fn colors_string(decl: &Declaration) -> String {
+  let (light, dark) = (hex_string(&decl.light), hex_string(&decl.dark));
+  if light == dark {
+    light
+  } else {
+    format!("(light: {}, dark: {})", light, dark)
+  }
+}
@@ -0,0 +1,6 @@
+mod diff;
+mod error;
+mod format;
+
+pub use self::diff::{ColorChange, Declaration, Diff, Modification, Rename};
+pub use self::error::Error;
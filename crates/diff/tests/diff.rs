@@ -0,0 +1,207 @@
+use diff::{Diff, Rename};
+use parser::{ast::Document, parse_document};
+use serde_json::json;
+
+const OLD: &str = r#"
+  $black: #000000
+  $white: #ffffff
+  $grey: #777777
+  $classic: (light: $black, dark: $white)
+
+  Background: (light: $white, dark: $black)
+  Separator: #000000 10%
+  Text {
+    Primary: $classic
+    Secondary: (light: $grey, dark: #A0A0A0)
+    Legacy: #333333
+    Link: #1E6FFF
+  }
+"#;
+
+const NEW: &str = r#"
+  $black: #111111
+  $white: #ffffff
+  $grey: #767676
+  $classic: (light: $black, dark: $white)
+
+  Background: (light: $white, dark: #1C1C1E)
+  Separator: #000000 10%
+  Text {
+    Primary: $classic
+    Secondary: (light: $grey, dark: #A0A0A0)
+    Hyperlink: #1E6FFF
+    Tertiary: (light: #AAAAAA, dark: #555555)
+  }
+"#;
+
+#[test]
+fn semantic_diff() {
+  let diff = Diff::between(&document(OLD), &document(NEW)).unwrap();
+
+  assert_eq!(
+    diff
+      .added
+      .iter()
+      .map(|decl| decl.path.as_str())
+      .collect::<Vec<_>>(),
+    vec!["Text.Tertiary"]
+  );
+  assert_eq!(
+    diff
+      .removed
+      .iter()
+      .map(|decl| decl.path.as_str())
+      .collect::<Vec<_>>(),
+    vec!["Text.Legacy"]
+  );
+  assert_eq!(
+    diff.renamed,
+    vec![Rename {
+      old_path: "Text.Link".to_string(),
+      new_path: "Text.Hyperlink".to_string(),
+    }]
+  );
+
+  let changed: Vec<(&str, Vec<String>)> = diff
+    .changed
+    .iter()
+    .map(|m| (m.path.as_str(), m.variables.clone()))
+    .collect();
+  assert_eq!(
+    changed,
+    vec![
+      ("Background", vec![]),
+      ("Text.Primary", vec!["black".to_string()]),
+      ("Text.Secondary", vec!["grey".to_string()]),
+    ]
+  );
+
+  // Background changed its dark color, but its light color still comes from $white.
+  assert!(diff.changed[0].light.is_none());
+  assert!(diff.changed[2].dark.is_none());
+}
+
+#[test]
+fn indirect_changes_name_the_edited_variable() {
+  let old = r#"
+    $brand: #1E6FFF
+    $accent: #FF5A1E
+    $ink: $brand
+    $highlight: $accent
+    Button {
+      $ink: (light: $ink, dark: #FFFFFF)
+      Text: $ink
+    }
+    Link: $highlight
+  "#;
+  let new = r#"
+    $brand: #0050E0
+    $accent: #FF5A1E
+    $warning: #FFB800
+    $ink: $brand
+    $highlight: $warning
+    Button {
+      $ink: (light: $ink, dark: #FFFFFF)
+      Text: $ink
+    }
+    Link: $highlight
+  "#;
+  let diff = Diff::between(&document(old), &document(new)).unwrap();
+
+  let changed: Vec<(&str, Vec<String>)> = diff
+    .changed
+    .iter()
+    .map(|m| (m.path.as_str(), m.variables.clone()))
+    .collect();
+  assert_eq!(
+    changed,
+    vec![
+      ("Button.Text", vec!["brand".to_string()]),
+      ("Link", vec!["highlight".to_string()]),
+    ]
+  );
+}
+
+#[test]
+fn identical_documents() {
+  let diff = Diff::between(&document(OLD), &document(OLD)).unwrap();
+  assert!(diff.is_empty());
+  assert_eq!(diff.to_string(), "No changes.\n");
+}
+
+#[test]
+fn ambiguous_renames_are_reported_as_added_and_removed() {
+  let diff = Diff::between(
+    &document("A: #000000\nB: #000000\n"),
+    &document("C: #000000\nD: #000000\n"),
+  )
+  .unwrap();
+  assert!(diff.renamed.is_empty());
+  assert_eq!(diff.added.len(), 2);
+  assert_eq!(diff.removed.len(), 2);
+}
+
+#[test]
+fn text_output() {
+  let diff = Diff::between(&document(OLD), &document(NEW)).unwrap();
+  assert_eq!(
+    diff.to_string(),
+    r#"Added:
+  + Text.Tertiary: (light: #AAAAAA, dark: #555555)
+Removed:
+  - Text.Legacy: #333333
+Renamed:
+  Text.Link → Text.Hyperlink
+Changed:
+  Background
+    dark: #000000 → #1C1C1E (ΔE 10.45)
+Changed indirectly by variables:
+  Text.Primary (via $black)
+    light: #000000 → #111111 (ΔE 5.06)
+  Text.Secondary (via $grey)
+    light: #777777 → #767676 (ΔE 0.40)
+"#
+  );
+}
+
+#[test]
+fn text_output_of_single_color() {
+  let diff = Diff::between(
+    &document("Separator: #000000 10%"),
+    &document("Separator: #1C1C1E 10%"),
+  )
+  .unwrap();
+  assert_eq!(
+    diff.to_string(),
+    "Changed:\n  Separator\n    #000000 10% → #1C1C1E 10% (ΔE 10.45)\n"
+  );
+}
+
+#[test]
+fn json_output() {
+  let diff = Diff::between(&document(OLD), &document(NEW)).unwrap();
+  let json = diff.to_json();
+
+  assert_eq!(
+    json["added"],
+    json!([{ "path": "Text.Tertiary", "light": "#AAAAAA", "dark": "#555555" }])
+  );
+  assert_eq!(
+    json["renamed"],
+    json!([{ "old-path": "Text.Link", "new-path": "Text.Hyperlink" }])
+  );
+  assert_eq!(
+    json["changed"][1],
+    json!({
+      "path": "Text.Primary",
+      "light": { "old": "#000000", "new": "#111111", "delta-e": 5.06 },
+      "dark": null,
+      "indirect": true,
+      "variables": ["black"],
+    })
+  );
+}
+
+fn document(contents: &str) -> Document {
+  parse_document(contents.to_string()).expect("Could not parse document")
+}
@@ -0,0 +1,118 @@
+use super::{FileSystem, OsFileSystem};
+use similar::{ChangeTag, TextDiff};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A modification of a single file on disk.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+  Created {
+    path: PathBuf,
+    new: Vec<u8>,
+  },
+  Changed {
+    path: PathBuf,
+    old: Vec<u8>,
+    new: Vec<u8>,
+  },
+  Deleted {
+    path: PathBuf,
+    old: Vec<u8>,
+  },
+}
+
+impl Change {
+  /// Compares `contents` with the file at `path`. Returns `None` if both are identical.
+  pub fn for_file(path: impl AsRef<Path>, contents: &[u8]) -> io::Result<Option<Change>> {
+    Self::for_file_in(&OsFileSystem, path, contents)
+  }
+
+  /// Like `for_file`, but reads the file from `fs`.
+  pub fn for_file_in(
+    fs: &dyn FileSystem,
+    path: impl AsRef<Path>,
+    contents: &[u8],
+  ) -> io::Result<Option<Change>> {
+    let path = path.as_ref();
+
+    if !fs.exists(path) {
+      return Ok(Some(Change::Created {
+        path: path.into(),
+        new: contents.to_vec(),
+      }));
+    }
+
+    let old = fs.read(path)?;
+
+    if old == contents {
+      return Ok(None);
+    }
+
+    Ok(Some(Change::Changed {
+      path: path.into(),
+      old,
+      new: contents.to_vec(),
+    }))
+  }
+
+  pub fn path(&self) -> &Path {
+    match self {
+      Self::Created { path, .. } | Self::Changed { path, .. } | Self::Deleted { path, .. } => path,
+    }
+  }
+
+  /// A short verb describing the kind of change, e.g. for log output.
+  pub fn verb(&self) -> &'static str {
+    match self {
+      Self::Created { .. } => "create",
+      Self::Changed { .. } => "change",
+      Self::Deleted { .. } => "delete",
+    }
+  }
+
+  /// Renders the change as a unified diff.
+  pub fn unified_diff(&self) -> String {
+    let (old, new) = self.texts();
+    let path = self.path().display().to_string();
+    let old_header = match self {
+      Self::Created { .. } => "/dev/null",
+      _ => &path,
+    };
+    let new_header = match self {
+      Self::Deleted { .. } => "/dev/null",
+      _ => &path,
+    };
+
+    TextDiff::from_lines(&old, &new)
+      .unified_diff()
+      .header(old_header, new_header)
+      .to_string()
+  }
+
+  /// Counts the inserted and deleted lines, e.g. for a diffstat.
+  pub fn line_stats(&self) -> (usize, usize) {
+    let (old, new) = self.texts();
+    let diff = TextDiff::from_lines(&old, &new);
+
+    diff
+      .iter_all_changes()
+      .fold((0, 0), |(insertions, deletions), change| {
+        match change.tag() {
+          ChangeTag::Insert => (insertions + 1, deletions),
+          ChangeTag::Delete => (insertions, deletions + 1),
+          ChangeTag::Equal => (insertions, deletions),
+        }
+      })
+  }
+
+  fn texts(&self) -> (String, String) {
+    match self {
+      Self::Created { new, .. } => (String::new(), String::from_utf8_lossy(new).into_owned()),
+      Self::Changed { old, new, .. } => (
+        String::from_utf8_lossy(old).into_owned(),
+        String::from_utf8_lossy(new).into_owned(),
+      ),
+      Self::Deleted { old, .. } => (String::from_utf8_lossy(old).into_owned(), String::new()),
+    }
+  }
+}
@@ -0,0 +1,166 @@
+use super::{write_file_atomically, FileTree};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file system operations needed to write generated files. Allows replacing the file system
+/// of the operating system, e.g. in tests.
+pub trait FileSystem {
+  fn exists(&self, path: &Path) -> bool;
+
+  fn is_dir(&self, path: &Path) -> bool;
+
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+  /// Reads all files below `root`. Returns an empty tree if `root` does not exist.
+  fn read_tree(&self, root: &Path) -> io::Result<FileTree>;
+
+  /// Replaces the file at `path` with `contents`.
+  fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+  /// Replaces the directory at `root` with the contents of `tree`.
+  fn write_tree(&self, root: &Path, tree: &FileTree) -> io::Result<()>;
+
+  /// Like `write_tree`, but keeps temporary files out of the parent of `root` by placing them in
+  /// `scratch` instead.
+  fn write_tree_via(&self, root: &Path, scratch: &Path, tree: &FileTree) -> io::Result<()>;
+}
+
+/// The file system of the operating system. Files and directories are replaced atomically.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+  fn exists(&self, path: &Path) -> bool {
+    path.exists()
+  }
+
+  fn is_dir(&self, path: &Path) -> bool {
+    path.is_dir()
+  }
+
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    fs::read(path)
+  }
+
+  fn read_tree(&self, root: &Path) -> io::Result<FileTree> {
+    let mut tree = FileTree::new();
+
+    if root.exists() {
+      collect_files(root, Path::new(""), &mut tree)?;
+    }
+
+    Ok(tree)
+  }
+
+  fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_file_atomically(path, contents)
+  }
+
+  fn write_tree(&self, root: &Path, tree: &FileTree) -> io::Result<()> {
+    tree.write_atomically(root)
+  }
+
+  fn write_tree_via(&self, root: &Path, scratch: &Path, tree: &FileTree) -> io::Result<()> {
+    tree.write_atomically_via(root, scratch)
+  }
+}
+
+fn collect_files(root: &Path, relative_path: &Path, tree: &mut FileTree) -> io::Result<()> {
+  for entry in fs::read_dir(root.join(relative_path))? {
+    let entry = entry?;
+    let path = relative_path.join(entry.file_name());
+
+    if entry.file_type()?.is_dir() {
+      collect_files(root, &path, tree)?;
+    } else {
+      tree.insert(path, fs::read(entry.path())?);
+    }
+  }
+
+  Ok(())
+}
+
+/// A file system held in memory. Directories exist implicitly as long as they contain files.
+#[derive(Debug, Default)]
+pub struct MemoryFileSystem {
+  files: RefCell<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryFileSystem {
+  pub fn new() -> Self {
+    MemoryFileSystem::default()
+  }
+
+  pub fn insert(&self, path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) {
+    self.files.borrow_mut().insert(path.into(), contents.into());
+  }
+
+  pub fn get(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+    self.files.borrow().get(path.as_ref()).cloned()
+  }
+
+  /// The paths of all files, sorted.
+  pub fn paths(&self) -> Vec<PathBuf> {
+    self.files.borrow().keys().cloned().collect()
+  }
+}
+
+impl FileSystem for MemoryFileSystem {
+  fn exists(&self, path: &Path) -> bool {
+    self.files.borrow().contains_key(path) || self.is_dir(path)
+  }
+
+  fn is_dir(&self, path: &Path) -> bool {
+    self
+      .files
+      .borrow()
+      .keys()
+      .any(|file| file != path && file.starts_with(path))
+  }
+
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    self.get(path).ok_or_else(|| {
+      io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{:?} does not exist", path),
+      )
+    })
+  }
+
+  fn read_tree(&self, root: &Path) -> io::Result<FileTree> {
+    let mut tree = FileTree::new();
+
+    for (path, contents) in self.files.borrow().iter() {
+      if let Ok(relative_path) = path.strip_prefix(root) {
+        if relative_path != Path::new("") {
+          tree.insert(relative_path, contents.clone());
+        }
+      }
+    }
+
+    Ok(tree)
+  }
+
+  fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+    self.insert(path, contents);
+    Ok(())
+  }
+
+  fn write_tree(&self, root: &Path, tree: &FileTree) -> io::Result<()> {
+    let mut files = self.files.borrow_mut();
+
+    files.retain(|path, _| !path.starts_with(root));
+    for (path, contents) in tree.iter() {
+      files.insert(root.join(path), contents.to_vec());
+    }
+
+    Ok(())
+  }
+
+  fn write_tree_via(&self, root: &Path, _scratch: &Path, tree: &FileTree) -> io::Result<()> {
+    self.write_tree(root, tree)
+  }
+}
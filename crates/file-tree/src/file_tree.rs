@@ -0,0 +1,201 @@
+use super::{Change, FileSystem, OsFileSystem};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An in-memory directory of files, keyed by their path relative to the directory's root.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileTree {
+  files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl FileTree {
+  pub fn new() -> Self {
+    FileTree::default()
+  }
+
+  pub fn insert(&mut self, path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) {
+    self.files.insert(path.into(), contents.into());
+  }
+
+  pub fn get(&self, path: impl AsRef<Path>) -> Option<&[u8]> {
+    self
+      .files
+      .get(path.as_ref())
+      .map(|contents| contents.as_slice())
+  }
+
+  pub fn len(&self) -> usize {
+    self.files.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.files.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (&Path, &[u8])> {
+    self
+      .files
+      .iter()
+      .map(|(path, contents)| (path.as_path(), contents.as_slice()))
+  }
+
+  /// Lists the changes writing the tree to `root` would cause. Files below `root` that are not
+  /// part of the tree are reported as deleted since `write_atomically` replaces the directory.
+  pub fn changes(&self, root: impl AsRef<Path>) -> io::Result<Vec<Change>> {
+    self.changes_in(&OsFileSystem, root)
+  }
+
+  /// Like `changes`, but compares the tree with the files in `fs`.
+  pub fn changes_in(&self, fs: &dyn FileSystem, root: impl AsRef<Path>) -> io::Result<Vec<Change>> {
+    let mut existing = fs.read_tree(root.as_ref())?.files;
+    let mut changes = vec![];
+
+    for (path, contents) in &self.files {
+      match existing.remove(path) {
+        None => changes.push(Change::Created {
+          path: path.clone(),
+          new: contents.clone(),
+        }),
+        Some(old) if &old != contents => changes.push(Change::Changed {
+          path: path.clone(),
+          old,
+          new: contents.clone(),
+        }),
+        Some(_) => {}
+      }
+    }
+
+    changes.extend(
+      existing
+        .into_iter()
+        .map(|(path, old)| Change::Deleted { path, old }),
+    );
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+
+    Ok(changes)
+  }
+
+  /// Replaces the directory at `root` with the contents of the tree.
+  ///
+  /// The tree is first written into a staging directory next to `root` which is then swapped in
+  /// by renaming. If anything goes wrong before the swap, `root` remains untouched.
+  pub fn write_atomically(&self, root: impl AsRef<Path>) -> io::Result<()> {
+    let root = root.as_ref();
+    self.swap_in(
+      root,
+      &sibling_path(root, "staging")?,
+      &sibling_path(root, "backup")?,
+    )
+  }
+
+  /// Like `write_atomically`, but stages the tree in a temporary directory inside `scratch`
+  /// instead of next to `root`, e.g. when the parent of `root` is owned by someone else. `scratch`
+  /// must be on the same file system as `root`. The temporary directory is removed again unless
+  /// it holds the only copy of the previous contents of `root`.
+  pub fn write_atomically_via(
+    &self,
+    root: impl AsRef<Path>,
+    scratch: impl AsRef<Path>,
+  ) -> io::Result<()> {
+    let root = root.as_ref();
+    let tmp_path = scratch.as_ref().join(hidden_name(root, "tmp")?);
+    let backup_path = tmp_path.join("backup");
+
+    if tmp_path.exists() {
+      fs::remove_dir_all(&tmp_path)?;
+    }
+    fs::create_dir_all(&tmp_path)?;
+
+    let result = self.swap_in(root, &tmp_path.join("staging"), &backup_path);
+    if result.is_ok() || !backup_path.exists() {
+      let _ = fs::remove_dir_all(&tmp_path);
+    }
+    result
+  }
+
+  /// Writes the tree into `staging_path` and swaps it in for `root`, moving the previous contents
+  /// of `root` to `backup_path` in the meantime.
+  fn swap_in(&self, root: &Path, staging_path: &Path, backup_path: &Path) -> io::Result<()> {
+    if staging_path.exists() {
+      fs::remove_dir_all(staging_path)?;
+    }
+
+    if let Err(err) = self.write_into(staging_path) {
+      let _ = fs::remove_dir_all(staging_path);
+      return Err(err);
+    }
+
+    if !root.exists() {
+      if let Err(err) = fs::rename(staging_path, root) {
+        let _ = fs::remove_dir_all(staging_path);
+        return Err(err);
+      }
+      return Ok(());
+    }
+
+    if backup_path.exists() {
+      fs::remove_dir_all(backup_path)?;
+    }
+
+    fs::rename(root, backup_path)?;
+
+    if let Err(err) = fs::rename(staging_path, root) {
+      fs::rename(backup_path, root)?;
+      let _ = fs::remove_dir_all(staging_path);
+      return Err(err);
+    }
+
+    fs::remove_dir_all(backup_path)
+  }
+
+  fn write_into(&self, root: &Path) -> io::Result<()> {
+    fs::create_dir_all(root)?;
+
+    for (path, contents) in &self.files {
+      let path = root.join(path);
+      if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+      }
+      fs::write(path, contents)?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Writes `contents` to a temporary file next to `path` and moves it into place, so that `path`
+/// never contains partially written data.
+pub fn write_file_atomically(path: impl AsRef<Path>, contents: &[u8]) -> io::Result<()> {
+  let path = path.as_ref();
+  let tmp_path = sibling_path(path, "tmp")?;
+
+  if let Err(err) = fs::write(&tmp_path, contents) {
+    let _ = fs::remove_file(&tmp_path);
+    return Err(err);
+  }
+
+  fs::rename(&tmp_path, path)
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> io::Result<PathBuf> {
+  Ok(path.with_file_name(hidden_name(path, suffix)?))
+}
+
+/// Returns the name of a hidden file belonging to `path`, e.g. `.Colors.xcassets.staging-42`.
+fn hidden_name(path: &Path, suffix: &str) -> io::Result<String> {
+  let file_name = path.file_name().ok_or_else(|| {
+    io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("{:?} does not have a file name", path),
+    )
+  })?;
+
+  Ok(format!(
+    ".{}.{}-{}",
+    file_name.to_string_lossy(),
+    suffix,
+    std::process::id()
+  ))
+}
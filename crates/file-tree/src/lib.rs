@@ -0,0 +1,7 @@
+mod change;
+mod file_system;
+mod file_tree;
+
+pub use self::change::Change;
+pub use self::file_system::{FileSystem, MemoryFileSystem, OsFileSystem};
+pub use self::file_tree::{write_file_atomically, FileTree};
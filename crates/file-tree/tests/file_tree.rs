@@ -0,0 +1,162 @@
+use file_tree::{write_file_atomically, Change, FileTree};
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+#[test]
+fn changes_against_missing_directory() {
+  let tmp_dir = TempDir::new("changes_against_missing_directory").expect("Create temp dir failed");
+  let tree = test_tree();
+
+  let changes = tree
+    .changes(tmp_dir.path().join("Missing"))
+    .expect("Could not compute changes");
+
+  assert_eq!(
+    changes,
+    vec![
+      Change::Created {
+        path: "A/Contents.json".into(),
+        new: b"a".to_vec()
+      },
+      Change::Created {
+        path: "B.txt".into(),
+        new: b"b".to_vec()
+      },
+    ]
+  );
+}
+
+#[test]
+fn changes_against_existing_directory() {
+  let tmp_dir = TempDir::new("changes_against_existing_directory").expect("Create temp dir failed");
+  fs::create_dir(tmp_dir.path().join("A")).unwrap();
+  fs::write(tmp_dir.path().join("A/Contents.json"), "a").unwrap();
+  fs::write(tmp_dir.path().join("B.txt"), "old").unwrap();
+  fs::write(tmp_dir.path().join("C.txt"), "c").unwrap();
+
+  let changes = test_tree()
+    .changes(tmp_dir.path())
+    .expect("Could not compute changes");
+
+  assert_eq!(
+    changes,
+    vec![
+      Change::Changed {
+        path: "B.txt".into(),
+        old: b"old".to_vec(),
+        new: b"b".to_vec()
+      },
+      Change::Deleted {
+        path: "C.txt".into(),
+        old: b"c".to_vec()
+      },
+    ]
+  );
+}
+
+#[test]
+fn write_atomically_replaces_directory() {
+  let tmp_dir =
+    TempDir::new("write_atomically_replaces_directory").expect("Create temp dir failed");
+  let root = tmp_dir.path().join("Root");
+  fs::create_dir(&root).unwrap();
+  fs::write(root.join("C.txt"), "c").unwrap();
+
+  test_tree()
+    .write_atomically(&root)
+    .expect("Could not write tree");
+
+  assert_eq!(
+    fs::read_to_string(root.join("A/Contents.json")).unwrap(),
+    "a"
+  );
+  assert_eq!(fs::read_to_string(root.join("B.txt")).unwrap(), "b");
+  assert!(!root.join("C.txt").exists());
+  assert!(test_tree().changes(&root).unwrap().is_empty());
+  assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn write_atomically_via_scratch_directory() {
+  let tmp_dir =
+    TempDir::new("write_atomically_via_scratch_directory").expect("Create temp dir failed");
+  let parent = tmp_dir.path().join("Parent");
+  let root = parent.join("Root");
+  fs::create_dir_all(&root).unwrap();
+  fs::write(root.join("C.txt"), "c").unwrap();
+
+  test_tree()
+    .write_atomically_via(&root, tmp_dir.path())
+    .expect("Could not write tree");
+
+  assert!(test_tree().changes(&root).unwrap().is_empty());
+  assert_eq!(fs::read_dir(&parent).unwrap().count(), 1);
+  assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn write_atomically_via_removes_scratch_directory_on_error() {
+  let tmp_dir = TempDir::new("write_atomically_via_removes_scratch_directory_on_error")
+    .expect("Create temp dir failed");
+  let file = tmp_dir.path().join("File.txt");
+  fs::write(&file, "file").unwrap();
+
+  // The tree can't be moved into place since the parent of its root is a file.
+  assert!(test_tree()
+    .write_atomically_via(file.join("Root"), tmp_dir.path())
+    .is_err());
+
+  assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn write_file_atomically_replaces_file() {
+  let tmp_dir =
+    TempDir::new("write_file_atomically_replaces_file").expect("Create temp dir failed");
+  let path = tmp_dir.path().join("File.swift");
+  fs::write(&path, "old").unwrap();
+
+  write_file_atomically(&path, b"new").expect("Could not write file");
+
+  assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+  assert_eq!(Change::for_file(&path, b"new").unwrap(), None);
+  assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn unified_diff() {
+  let change = Change::Changed {
+    path: Path::new("File.swift").into(),
+    old: b"a\nb\nc\n".to_vec(),
+    new: b"a\nB\nc\n".to_vec(),
+  };
+
+  assert_eq!(
+    change.unified_diff(),
+    "--- File.swift\n+++ File.swift\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+  );
+}
+
+#[test]
+fn line_stats() {
+  let change = Change::Changed {
+    path: Path::new("File.swift").into(),
+    old: b"a\nb\nc\n".to_vec(),
+    new: b"a\nB\nc\nd\n".to_vec(),
+  };
+  assert_eq!(change.line_stats(), (2, 1));
+
+  let change = Change::Deleted {
+    path: Path::new("File.swift").into(),
+    old: b"a\nb\n".to_vec(),
+  };
+  assert_eq!(change.line_stats(), (0, 2));
+}
+
+fn test_tree() -> FileTree {
+  let mut tree = FileTree::new();
+  tree.insert("B.txt", "b");
+  tree.insert("A/Contents.json", "a");
+  tree
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// The checks performed by `lint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+  /// A `@contrast` requirement is not met.
+  Contrast,
+  /// A variable is never referenced.
+  UnusedVariable,
+  /// The light and dark colors of a color set are identical.
+  RedundantColorSet,
+  /// A declaration uses a literal color although a variable with the same color exists.
+  HardcodedColor,
+  /// Two colors are distinct but barely distinguishable.
+  NearDuplicateColor,
+}
+
+impl Rule {
+  pub const ALL: [Rule; 5] = [
+    Rule::Contrast,
+    Rule::UnusedVariable,
+    Rule::RedundantColorSet,
+    Rule::HardcodedColor,
+    Rule::NearDuplicateColor,
+  ];
+
+  pub fn default_severity(&self) -> Severity {
+    match self {
+      Self::Contrast => Severity::Error,
+      Self::UnusedVariable
+      | Self::RedundantColorSet
+      | Self::HardcodedColor
+      | Self::NearDuplicateColor => Severity::Warning,
+    }
+  }
+}
+
+impl FromStr for Rule {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Rule, ()> {
+    Rule::ALL
+      .into_iter()
+      .find(|rule| rule.to_string() == s.to_lowercase())
+      .ok_or(())
+  }
+}
+
+impl fmt::Display for Rule {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Contrast => "contrast",
+      Self::UnusedVariable => "unused-variable",
+      Self::RedundantColorSet => "redundant-color-set",
+      Self::HardcodedColor => "hardcoded-color",
+      Self::NearDuplicateColor => "near-duplicate-color",
+    })
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+  /// The rule is disabled.
+  Allow,
+  Warning,
+  Error,
+}
+
+impl FromStr for Severity {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Severity, ()> {
+    match s.to_lowercase().as_ref() {
+      "allow" => Ok(Severity::Allow),
+      "warning" => Ok(Severity::Warning),
+      "error" => Ok(Severity::Error),
+      _ => Err(()),
+    }
+  }
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Allow => "allow",
+      Self::Warning => "warning",
+      Self::Error => "error",
+    })
+  }
+}
+
+/// Determines which rules are checked and how severe their issues are.
+#[derive(Debug, Clone)]
+pub struct Config {
+  severities: HashMap<Rule, Severity>,
+  /// Colors with a CIE76 color difference (ΔE) below this value are reported by
+  /// `Rule::NearDuplicateColor`. A difference of about 2.3 is just noticeable.
+  pub near_duplicate_threshold: f32,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      severities: HashMap::new(),
+      near_duplicate_threshold: 2.3,
+    }
+  }
+}
+
+impl Config {
+  pub fn severity(&self, rule: Rule) -> Severity {
+    self
+      .severities
+      .get(&rule)
+      .copied()
+      .unwrap_or_else(|| rule.default_severity())
+  }
+
+  pub fn set_severity(&mut self, rule: Rule, severity: Severity) {
+    self.severities.insert(rule, severity);
+  }
+}
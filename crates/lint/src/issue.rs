@@ -0,0 +1,17 @@
+use super::{Rule, Severity};
+use parser::ast::Span;
+
+/// A problem found in a stylesheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+  pub rule: Rule,
+  pub severity: Severity,
+  pub span: Span,
+  pub message: String,
+}
+
+/// A problem found by a rule, before its severity has been determined.
+pub(crate) struct Finding {
+  pub span: Span,
+  pub message: String,
+}
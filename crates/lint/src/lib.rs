@@ -0,0 +1,46 @@
+mod config;
+mod error;
+mod issue;
+mod rules;
+mod suppressions;
+mod walk;
+
+pub use self::config::{Config, Rule, Severity};
+pub use self::error::Error;
+pub use self::issue::Issue;
+
+use self::suppressions::Suppressions;
+use parser::ast::Document;
+
+/// Checks `doc` for issues, sorted by their location in the stylesheet. `source` is the text `doc`
+/// has been parsed from, which is searched for `// lint:allow(rule)` comments.
+pub fn lint(doc: &Document, source: &str, config: &Config) -> Result<Vec<Issue>, Error> {
+  // Inherited declarations are checked like the declarations of the extending ruleset.
+  let doc = &parser::expand_extends(&parser::expand_scales(doc))?;
+  let ctx = rules::Context::new(doc, config);
+  let suppressions = Suppressions::parse(source);
+  let mut issues = vec![];
+
+  for rule in Rule::ALL {
+    let severity = config.severity(rule);
+    if severity == Severity::Allow {
+      continue;
+    }
+
+    for finding in rules::check(rule, &ctx)? {
+      let (line, _) = finding.span.line_column(source);
+      if suppressions.is_suppressed(rule, line) {
+        continue;
+      }
+      issues.push(Issue {
+        rule,
+        severity,
+        span: finding.span,
+        message: finding.message,
+      });
+    }
+  }
+
+  issues.sort_by_key(|issue| issue.span.start);
+  Ok(issues)
+}
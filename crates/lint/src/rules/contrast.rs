@@ -0,0 +1,67 @@
+use super::super::issue::Finding;
+use super::super::Error;
+use super::Context;
+use parser::ast::{ContrastRequirement, DocumentItem};
+use parser::color_space::{composite, contrast_ratio, BLACK, WHITE};
+use parser::resolved::Document as ResolvedDocument;
+
+pub(super) fn check(ctx: &Context) -> Result<Vec<Finding>, Error> {
+  let resolved = ResolvedDocument::derive_from(ctx.doc)?;
+  let mut findings = vec![];
+
+  for item in &ctx.doc.items {
+    if let DocumentItem::Contrast(requirement) = item {
+      findings.extend(check_requirement(&resolved, requirement));
+    }
+  }
+
+  Ok(findings)
+}
+
+fn check_requirement(doc: &ResolvedDocument, requirement: &ContrastRequirement) -> Vec<Finding> {
+  let mut findings = vec![];
+  let mut resolve = |path: &str| match doc.find_declaration(path) {
+    Some(decl) => Some(decl.appearances()),
+    None => {
+      findings.push(Finding {
+        span: requirement.span,
+        message: format!("Could not find declaration {}.", path),
+      });
+      None
+    }
+  };
+
+  let foreground = resolve(&requirement.foreground);
+  let background = resolve(&requirement.background);
+  let (foreground, background) = match (foreground, background) {
+    (Some(foreground), Some(background)) => (foreground, background),
+    _ => return findings,
+  };
+
+  for (appearance, foreground, background, base) in [
+    ("light", foreground.0, background.0, &WHITE),
+    ("dark", foreground.1, background.1, &BLACK),
+  ] {
+    let background = composite(background, base);
+    let foreground = composite(foreground, &background);
+    let ratio = contrast_ratio(&foreground, &background);
+    let minimum_ratio = requirement.level.minimum_ratio();
+
+    if ratio < minimum_ratio {
+      findings.push(Finding {
+        span: requirement.span,
+        message: format!(
+          "{} on {} has a contrast ratio of {:.2}:1 in the {} appearance, {} requires {}:1.",
+          requirement.foreground,
+          requirement.background,
+          ratio,
+          appearance,
+          requirement.level,
+          minimum_ratio
+        ),
+      });
+    }
+  }
+
+  findings
+}
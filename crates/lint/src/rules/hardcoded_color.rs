@@ -0,0 +1,54 @@
+use super::super::issue::Finding;
+use super::super::walk;
+use super::Context;
+use parser::ast::{Color, ColorSetValue, Value};
+use parser::color_space::{hex_string, is_same_color};
+
+pub(super) fn check(ctx: &Context) -> Vec<Finding> {
+  let mut findings = vec![];
+
+  for entry in walk::declarations(ctx.doc) {
+    let decl = entry.decl;
+    let variables: Vec<(&str, &Color)> = entry
+      .vars
+      .visible_variables()
+      .into_iter()
+      .filter_map(|variable| match &variable.value {
+        Value::Color(color) => Some((variable.identifier.as_str(), color)),
+        _ => None,
+      })
+      .collect();
+    let literals: Vec<(Option<&str>, &Color)> = match &decl.value {
+      Value::Color(color) => vec![(None, color)],
+      Value::ColorSet(colorset) => [("light", &colorset.light), ("dark", &colorset.dark)]
+        .into_iter()
+        .filter_map(|(property, value)| match value {
+          ColorSetValue::Color(color) => Some((Some(property), color)),
+          ColorSetValue::Variable(_) | ColorSetValue::Reference(_) => None,
+        })
+        .collect(),
+      Value::Variable(_) | Value::Reference(_) | Value::Scale(_) => vec![],
+    };
+
+    for (property, literal) in literals {
+      if let Some((variable, _)) = variables
+        .iter()
+        .find(|(_, color)| is_same_color(color, literal))
+      {
+        let usage = match property {
+          Some(property) => format!("{} as its {} color", hex_string(literal), property),
+          None => hex_string(literal),
+        };
+        findings.push(Finding {
+          span: decl.span,
+          message: format!(
+            "{} uses {}, which is available as ${}.",
+            entry.name, usage, variable
+          ),
+        });
+      }
+    }
+  }
+
+  findings
+}
@@ -0,0 +1,52 @@
+mod contrast;
+mod hardcoded_color;
+mod near_duplicate_color;
+mod redundant_color_set;
+mod unused_variable;
+
+use super::issue::Finding;
+use super::{Config, Error, Rule};
+use parser::ast::{Color, Document, Value};
+use parser::{ResolvedVariable, VarContext};
+
+pub(crate) struct Context<'a> {
+  pub doc: &'a Document,
+  pub config: &'a Config,
+}
+
+impl<'a> Context<'a> {
+  pub fn new(doc: &'a Document, config: &'a Config) -> Self {
+    Context { doc, config }
+  }
+}
+
+/// Resolves `value` into its light and dark color using the variables visible to it.
+fn resolve(vars: &VarContext, value: &Value) -> Result<(Color, Color), Error> {
+  Ok(match value {
+    Value::Color(color) => (color.clone(), color.clone()),
+    Value::ColorSet(colorset) => {
+      let colorset = vars.resolve_colorset(colorset)?;
+      (colorset.light, colorset.dark)
+    }
+    Value::Variable(variable) => appearances(vars.resolve(variable)?),
+    Value::Reference(reference) => appearances(vars.resolve_reference(reference)?),
+    Value::Scale(_) => unreachable!("Scales are expanded before linting."),
+  })
+}
+
+fn appearances(resolved: ResolvedVariable) -> (Color, Color) {
+  match resolved {
+    ResolvedVariable::Color(color) => (color.clone(), color),
+    ResolvedVariable::ColorSet(colorset) => (colorset.light, colorset.dark),
+  }
+}
+
+pub(crate) fn check(rule: Rule, ctx: &Context) -> Result<Vec<Finding>, Error> {
+  match rule {
+    Rule::Contrast => contrast::check(ctx),
+    Rule::UnusedVariable => Ok(unused_variable::check(ctx)),
+    Rule::RedundantColorSet => redundant_color_set::check(ctx),
+    Rule::HardcodedColor => Ok(hardcoded_color::check(ctx)),
+    Rule::NearDuplicateColor => Ok(near_duplicate_color::check(ctx)),
+  }
+}
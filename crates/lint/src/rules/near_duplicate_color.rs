@@ -0,0 +1,62 @@
+use super::super::issue::Finding;
+use super::super::walk;
+use super::Context;
+use parser::ast::{Color, ColorSetValue, Span, Value};
+use parser::color_space::{color_difference, hex_string, is_same_color};
+
+pub(super) fn check(ctx: &Context) -> Vec<Finding> {
+  let entries = walk::variables(ctx.doc)
+    .into_iter()
+    .chain(walk::declarations(ctx.doc));
+
+  // The distinct literal colors of the stylesheet, along with where they are used first.
+  let mut colors: Vec<(&Color, String, Span)> = vec![];
+  for entry in entries {
+    let decl = entry.decl;
+    let literals: Vec<&Color> = match &decl.value {
+      Value::Color(color) => vec![color],
+      Value::ColorSet(colorset) => [&colorset.light, &colorset.dark]
+        .into_iter()
+        .filter_map(|value| match value {
+          ColorSetValue::Color(color) => Some(color),
+          ColorSetValue::Variable(_) | ColorSetValue::Reference(_) => None,
+        })
+        .collect(),
+      Value::Variable(_) | Value::Reference(_) | Value::Scale(_) => vec![],
+    };
+
+    for literal in literals {
+      if !colors
+        .iter()
+        .any(|(color, _, _)| is_same_color(color, literal))
+      {
+        colors.push((literal, entry.name.clone(), decl.span));
+      }
+    }
+  }
+
+  let mut findings = vec![];
+  for (idx, (color, _, span)) in colors.iter().enumerate() {
+    let similar = colors[..idx].iter().find_map(|(other, name, _)| {
+      let difference = color_difference(color, other);
+      let is_similar =
+        (color.a - other.a).abs() < 0.001 && difference < ctx.config.near_duplicate_threshold;
+      is_similar.then_some((other, name, difference))
+    });
+
+    if let Some((other, name, difference)) = similar {
+      findings.push(Finding {
+        span: *span,
+        message: format!(
+          "{} is barely distinguishable from {} used by {} (ΔE {:.2}).",
+          hex_string(color),
+          hex_string(other),
+          name,
+          difference
+        ),
+      });
+    }
+  }
+
+  findings
+}
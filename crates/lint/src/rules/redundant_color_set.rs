@@ -0,0 +1,32 @@
+use super::super::issue::Finding;
+use super::super::walk;
+use super::super::Error;
+use super::{resolve, Context};
+use parser::ast::Value;
+use parser::color_space::is_same_color;
+
+pub(super) fn check(ctx: &Context) -> Result<Vec<Finding>, Error> {
+  let mut findings = vec![];
+
+  for entry in walk::variables(ctx.doc)
+    .into_iter()
+    .chain(walk::declarations(ctx.doc))
+  {
+    if !matches!(entry.decl.value, Value::ColorSet(_)) {
+      continue;
+    }
+
+    let (light, dark) = resolve(&entry.vars, &entry.decl.value)?;
+    if is_same_color(&light, &dark) {
+      findings.push(Finding {
+        span: entry.decl.span,
+        message: format!(
+          "The light and dark colors of {} are identical, use a single color instead.",
+          entry.name
+        ),
+      });
+    }
+  }
+
+  Ok(findings)
+}
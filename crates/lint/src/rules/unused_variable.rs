@@ -0,0 +1,58 @@
+use super::super::issue::Finding;
+use super::super::walk;
+use super::Context;
+use parser::ast::{ColorSetValue, Span, Value};
+use std::collections::HashSet;
+
+pub(super) fn check(ctx: &Context) -> Vec<Finding> {
+  let variables = walk::variables(ctx.doc);
+  // The start offsets of the variables referenced anywhere in the stylesheet.
+  let mut used: HashSet<usize> = HashSet::new();
+
+  for entry in variables.iter().chain(&walk::declarations(ctx.doc)) {
+    let references = match &entry.decl.value {
+      Value::Variable(variable) => vec![variable],
+      Value::ColorSet(colorset) => [&colorset.light, &colorset.dark]
+        .into_iter()
+        .filter_map(|value| match value {
+          ColorSetValue::Variable(variable) => Some(variable),
+          ColorSetValue::Reference(_) | ColorSetValue::Color(_) => None,
+        })
+        .collect(),
+      Value::Reference(_) | Value::Color(_) | Value::Scale(_) => vec![],
+    };
+
+    let referrer = entry.is_variable.then_some(entry.decl);
+    for reference in references {
+      if let Some(variable) = entry.vars.find(reference, referrer) {
+        used.insert(variable.span.start);
+      }
+    }
+  }
+
+  // The steps of a scale share its span, so they are used as soon as one of them is used and are
+  // reported together otherwise.
+  let mut unused: Vec<(Span, Vec<String>)> = vec![];
+  for entry in variables {
+    let span = entry.decl.span;
+    if used.contains(&span.start) {
+      continue;
+    }
+    match unused.last_mut() {
+      Some((last, names)) if *last == span => names.push(entry.name),
+      _ => unused.push((span, vec![entry.name])),
+    }
+  }
+
+  unused
+    .into_iter()
+    .map(|(span, names)| Finding {
+      span,
+      message: match names.as_slice() {
+        [name] => format!("Variable {} is never used.", name),
+        [first, .., last] => format!("Variables {}…{} are never used.", first, last),
+        [] => unreachable!(),
+      },
+    })
+    .collect()
+}
@@ -0,0 +1,49 @@
+use super::Rule;
+use std::collections::{HashMap, HashSet};
+
+/// The rules suppressed by `// lint:allow(rule, …)` comments, by line.
+///
+/// A comment at the end of a line suppresses issues on that line. A comment on a line of its own
+/// suppresses issues on the following line.
+pub(crate) struct Suppressions {
+  lines: HashMap<usize, HashSet<Rule>>,
+}
+
+const MARKER: &str = "lint:allow(";
+
+impl Suppressions {
+  pub fn parse(source: &str) -> Self {
+    let mut lines: HashMap<usize, HashSet<Rule>> = HashMap::new();
+
+    for (idx, line) in source.lines().enumerate() {
+      let comment = match line.find("//") {
+        Some(start) => &line[start..],
+        None => continue,
+      };
+      let arguments = match comment.find(MARKER) {
+        Some(start) => &comment[start + MARKER.len()..],
+        None => continue,
+      };
+      let arguments = arguments.split(')').next().unwrap_or_default();
+      let rules = arguments
+        .split(',')
+        .filter_map(|rule| rule.trim().parse::<Rule>().ok());
+
+      let line_number = if line.trim_start().starts_with("//") {
+        idx + 2
+      } else {
+        idx + 1
+      };
+      lines.entry(line_number).or_default().extend(rules);
+    }
+
+    Suppressions { lines }
+  }
+
+  pub fn is_suppressed(&self, rule: Rule, line: usize) -> bool {
+    self
+      .lines
+      .get(&line)
+      .is_some_and(|rules| rules.contains(&rule))
+  }
+}
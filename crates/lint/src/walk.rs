@@ -0,0 +1,99 @@
+use parser::ast::{Declaration, Document, DocumentItem, RuleSet, RuleSetItem, Value};
+use parser::VarContext;
+
+/// A variable or declaration of the stylesheet along with the variables visible to it.
+pub(crate) struct Entry<'a> {
+  /// `$identifier` for variables and the dotted path for declarations, e.g. `Text.Primary`.
+  pub name: String,
+  pub decl: &'a Declaration<Value>,
+  pub is_variable: bool,
+  pub vars: VarContext<'a>,
+}
+
+/// Returns all declarations of `doc`, depth-first in the order of the stylesheet. Inherited
+/// declarations are only returned for the first ruleset containing them.
+pub(crate) fn declarations(doc: &Document) -> Vec<Entry<'_>> {
+  entries(doc)
+    .into_iter()
+    .filter(|entry| !entry.is_variable)
+    .collect()
+}
+
+/// Returns all variables of `doc`, including those declared in rulesets.
+pub(crate) fn variables(doc: &Document) -> Vec<Entry<'_>> {
+  entries(doc)
+    .into_iter()
+    .filter(|entry| entry.is_variable)
+    .collect()
+}
+
+fn entries(doc: &Document) -> Vec<Entry<'_>> {
+  let vars = VarContext::derive_from(doc);
+  let mut entries = vec![];
+
+  for item in &doc.items {
+    match item {
+      DocumentItem::Variable(variable) => entries.push(Entry {
+        name: format!("${}", variable.identifier),
+        decl: variable,
+        is_variable: true,
+        vars: vars.clone(),
+      }),
+      DocumentItem::Declaration(decl) => entries.push(Entry {
+        name: decl.identifier.clone(),
+        decl,
+        is_variable: false,
+        vars: vars.clone(),
+      }),
+      DocumentItem::RuleSet(ruleset) => {
+        collect_entries(&ruleset.identifier, ruleset, &vars, &mut entries)
+      }
+      DocumentItem::Contrast(_) => {}
+    }
+  }
+
+  // Rulesets inheriting a declaration via @extend share its span and identifier, only its first
+  // occurrence is checked so that every line of the stylesheet is reported once. Scale steps share
+  // the span of their scale as well, but not its identifier.
+  let mut seen = vec![];
+  entries.retain(|entry| {
+    let key = (entry.decl.span, &entry.decl.identifier);
+    let first = !seen.contains(&key);
+    seen.push(key);
+    first
+  });
+  entries
+}
+
+fn collect_entries<'a>(
+  path: &str,
+  ruleset: &'a RuleSet,
+  vars: &VarContext<'a>,
+  entries: &mut Vec<Entry<'a>>,
+) {
+  let vars = vars.scoped(ruleset);
+
+  for item in &ruleset.items {
+    match item {
+      RuleSetItem::Variable(variable) => entries.push(Entry {
+        name: format!("${}", variable.identifier),
+        decl: variable,
+        is_variable: true,
+        vars: vars.clone(),
+      }),
+      RuleSetItem::Declaration(decl) => entries.push(Entry {
+        name: format!("{}.{}", path, decl.identifier),
+        decl,
+        is_variable: false,
+        vars: vars.clone(),
+      }),
+      RuleSetItem::Extend(_) => {}
+      RuleSetItem::RuleSet(ruleset) => collect_entries(
+        &format!("{}.{}", path, ruleset.identifier),
+        ruleset,
+        &vars,
+        entries,
+      ),
+    }
+  }
+}
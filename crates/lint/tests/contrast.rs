@@ -0,0 +1,55 @@
+use lint::{lint, Config};
+use parser::parse_document;
+
+#[test]
+fn contrast_requirements() {
+  let source = r#"
+    $grey: #777777
+    Background: (light: #ffffff, dark: #000000)
+    Text {
+      Primary: (light: #000000, dark: #ffffff)
+      Secondary: (light: $grey, dark: #333333)
+      Disabled: #000000 30%
+    }
+
+    @contrast(Text.Primary on Background, AAA)
+    @contrast(Text.Secondary on Background, AA-large)
+    @contrast(Text.Secondary on Background, AA)
+    @contrast(Text.Disabled on Background, AA)
+    @contrast(Text.Tertiary on Background, AA)
+  "#;
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+  let issues = lint(&doc, source, &Config::default()).expect("Could not lint document");
+
+  let messages: Vec<(usize, &str)> = issues
+    .iter()
+    .map(|issue| (issue.span.line_column(source).0, issue.message.as_str()))
+    .collect();
+
+  assert_eq!(
+    messages,
+    vec![
+      (
+        11,
+        "Text.Secondary on Background has a contrast ratio of 1.66:1 in the dark appearance, AA-large requires 3:1."
+      ),
+      (
+        12,
+        "Text.Secondary on Background has a contrast ratio of 4.48:1 in the light appearance, AA requires 4.5:1."
+      ),
+      (
+        12,
+        "Text.Secondary on Background has a contrast ratio of 1.66:1 in the dark appearance, AA requires 4.5:1."
+      ),
+      (
+        13,
+        "Text.Disabled on Background has a contrast ratio of 2.10:1 in the light appearance, AA requires 4.5:1."
+      ),
+      (
+        13,
+        "Text.Disabled on Background has a contrast ratio of 1.00:1 in the dark appearance, AA requires 4.5:1."
+      ),
+      (14, "Could not find declaration Text.Tertiary."),
+    ]
+  );
+}
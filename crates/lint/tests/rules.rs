@@ -0,0 +1,317 @@
+use lint::{lint, Config, Rule, Severity};
+use parser::parse_document;
+
+#[test]
+fn unused_variables() {
+  let source = r#"
+    $black: #000000
+    $white: #ffffff
+    $grey: #888888
+    $ink: $black
+    $unused: (light: $grey, dark: #333333)
+    Text: (light: $ink, dark: $white)
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::UnusedVariable),
+    vec![(6, "Variable $unused is never used.".to_string())]
+  );
+}
+
+#[test]
+fn unused_scoped_variables() {
+  let source = r#"
+    $bg: #ffffff
+    $accent: #0000ff
+    NumericInput {
+      $bg: #cccccc
+      $accent: $accent 50%
+      $unused: #000000
+      Background: $bg
+      Accent: $accent
+    }
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::UnusedVariable),
+    vec![
+      (2, "Variable $bg is never used.".to_string()),
+      (7, "Variable $unused is never used.".to_string())
+    ]
+  );
+}
+
+#[test]
+fn unused_scales() {
+  let source = r#"
+    $blue: scale(#1E6FFF, steps: 5)
+    $grey: scale(#808080)
+    $red: scale(#ff0000, steps: 1)
+    Accent: $blue300
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::UnusedVariable),
+    vec![
+      (3, "Variables $grey100…$grey900 are never used.".to_string()),
+      (4, "Variable $red100 is never used.".to_string())
+    ]
+  );
+}
+
+#[test]
+fn redundant_color_sets() {
+  let source = r#"
+    $white: #ffffff
+    $paper: (light: $white, dark: #FFFFFF)
+    Text {
+      Primary: (light: #151618 50%, dark: #151618 50%)
+      Secondary: (light: #151618, dark: #151618 50%)
+      Paper: $paper
+    }
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::RedundantColorSet),
+    vec![
+      (
+        3,
+        "The light and dark colors of $paper are identical, use a single color instead."
+          .to_string()
+      ),
+      (
+        5,
+        "The light and dark colors of Text.Primary are identical, use a single color instead."
+          .to_string()
+      ),
+    ]
+  );
+}
+
+#[test]
+fn hardcoded_colors() {
+  let source = r#"
+    $white: #ffffff
+    $translucent: #000000 50%
+    $dynamic: (light: #000000, dark: #ffffff)
+    Background: #FFFFFF
+    Overlay: (light: #000000 50%, dark: #000000)
+    Text: $white
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::HardcodedColor),
+    vec![
+      (
+        5,
+        "Background uses #FFFFFF, which is available as $white.".to_string()
+      ),
+      (
+        6,
+        "Overlay uses #000000 50% as its light color, which is available as $translucent."
+          .to_string()
+      ),
+    ]
+  );
+}
+
+#[test]
+fn hardcoded_colors_in_scope() {
+  let source = r#"
+    $white: #ffffff
+    Key {
+      $white: #fefefe
+      Background: #FFFFFF
+      Face: #FEFEFE
+    }
+    Background: #FEFEFE
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::HardcodedColor),
+    vec![(
+      6,
+      "Key.Face uses #FEFEFE, which is available as $white.".to_string()
+    )]
+  );
+}
+
+#[test]
+fn hardcoded_colors_in_colorsets() {
+  let source = r#"
+    $white: #ffffff
+    Background: (light: #ffffff, dark: #ffffff)
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::HardcodedColor),
+    vec![
+      (
+        3,
+        "Background uses #FFFFFF as its light color, which is available as $white.".to_string()
+      ),
+      (
+        3,
+        "Background uses #FFFFFF as its dark color, which is available as $white.".to_string()
+      ),
+    ]
+  );
+}
+
+#[test]
+fn hardcoded_colors_inherited_via_extend() {
+  let source = r#"
+    $white: #ffffff
+    Button {
+      Background: $white
+    }
+    PrimaryButton {
+      @extend Button
+      Text: #FFFFFF
+    }
+    SecondaryButton {
+      @extend PrimaryButton
+    }
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::HardcodedColor),
+    vec![(
+      8,
+      "PrimaryButton.Text uses #FFFFFF, which is available as $white.".to_string()
+    )]
+  );
+}
+
+#[test]
+fn inherited_declarations_reported_once() {
+  let source = r#"
+    $red: #FF0000
+    Button {
+      Background: #FF0000
+      Border: (light: #000000, dark: #000000)
+    }
+    PrimaryButton {
+      @extend Button
+    }
+    SecondaryButton {
+      @extend Button
+    }
+  "#;
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+  let summary: Vec<_> = lint(&doc, source, &Config::default())
+    .expect("Could not lint document")
+    .iter()
+    .map(|issue| (issue.span.line_column(source).0, issue.rule))
+    .collect();
+
+  assert_eq!(
+    summary,
+    vec![
+      (2, Rule::UnusedVariable),
+      (4, Rule::HardcodedColor),
+      (5, Rule::RedundantColorSet)
+    ]
+  );
+}
+
+#[test]
+fn near_duplicate_colors() {
+  let source = r#"
+    $ink: #151618
+    Text {
+      Primary: $ink
+      Secondary: (light: #151719, dark: #151618 50%)
+      Tertiary: #151719
+      Quaternary: #303030
+    }
+  "#;
+
+  assert_eq!(
+    issues(source, Rule::NearDuplicateColor),
+    vec![(
+      5,
+      "#151719 is barely distinguishable from #151618 used by $ink (ΔE 0.63).".to_string()
+    )]
+  );
+}
+
+#[test]
+fn severities() {
+  let source = r#"
+    $unused: #000000
+    $white: #ffffff
+    Background: (light: #ffffff, dark: #fffffe)
+    Text: $white
+  "#;
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+
+  let issues = lint(&doc, source, &Config::default()).expect("Could not lint document");
+  let summary: Vec<(Rule, Severity)> = issues
+    .iter()
+    .map(|issue| (issue.rule, issue.severity))
+    .collect();
+  assert_eq!(
+    summary,
+    vec![
+      (Rule::UnusedVariable, Severity::Warning),
+      (Rule::HardcodedColor, Severity::Warning),
+      (Rule::NearDuplicateColor, Severity::Warning),
+    ]
+  );
+
+  let mut config = Config::default();
+  config.set_severity(Rule::UnusedVariable, Severity::Error);
+  config.set_severity(Rule::NearDuplicateColor, Severity::Allow);
+  let issues = lint(&doc, source, &config).expect("Could not lint document");
+  let summary: Vec<(Rule, Severity)> = issues
+    .iter()
+    .map(|issue| (issue.rule, issue.severity))
+    .collect();
+  assert_eq!(
+    summary,
+    vec![
+      (Rule::UnusedVariable, Severity::Error),
+      (Rule::HardcodedColor, Severity::Warning),
+    ]
+  );
+}
+
+#[test]
+fn suppressions() {
+  let source = r#"
+    // lint:allow(unused-variable)
+    $unused: #000000
+    $white: #ffffff
+    $gray: #888888 // lint:allow(unused-variable, hardcoded-color)
+    Background: #ffffff // lint:allow(hardcoded-color)
+    // lint:allow(unused-variable)
+    Foreground: #ffffff
+    Text: $white
+  "#;
+
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+  let issues = lint(&doc, source, &Config::default()).expect("Could not lint document");
+  let summary: Vec<(usize, Rule)> = issues
+    .iter()
+    .map(|issue| (issue.span.line_column(source).0, issue.rule))
+    .collect();
+
+  assert_eq!(summary, vec![(8, Rule::HardcodedColor)]);
+}
+
+fn issues(source: &str, rule: Rule) -> Vec<(usize, String)> {
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+  let mut config = Config::default();
+  for other in Rule::ALL {
+    if other != rule {
+      config.set_severity(other, Severity::Allow);
+    }
+  }
+
+  lint(&doc, source, &config)
+    .expect("Could not lint document")
+    .into_iter()
+    .map(|issue| (issue.span.line_column(source).0, issue.message))
+    .collect()
+}
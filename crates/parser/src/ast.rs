@@ -1,80 +1,200 @@
+use std::fmt;
+
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Document {
   pub items: Vec<DocumentItem>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
 pub enum DocumentItem {
   Variable(Declaration<Value>),
   RuleSet(RuleSet),
   Declaration(Declaration<Value>),
+  Contrast(ContrastRequirement),
+}
+
+/// A range of bytes in the stylesheet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+  pub start: usize,
+  pub end: usize,
 }
 
+/// Requires a minimum contrast between two declarations, e.g.
+/// `@contrast(Text.Primary on Background, AA)`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContrastRequirement {
+  /// The dotted path of the foreground declaration.
+  pub foreground: String,
+  /// The dotted path of the background declaration.
+  pub background: String,
+  pub level: ContrastLevel,
+  pub span: Span,
+}
+
+/// The WCAG 2 conformance levels for contrast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContrastLevel {
+  AA,
+  #[cfg_attr(feature = "serde", serde(rename = "AA-large"))]
+  AALarge,
+  AAA,
+  #[cfg_attr(feature = "serde", serde(rename = "AAA-large"))]
+  AAALarge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
   pub r: u8,
   pub g: u8,
   pub b: u8,
   pub a: f32,
+  /// The linear sRGB components of a color function outside of the sRGB gamut, before `r`, `g`
+  /// and `b` have been mapped into it. Components outside of 0...1 describe the wider gamut, e.g.
+  /// for converting the color into Display P3.
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub linear: Option<[f64; 3]>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
 pub enum Value {
   Variable(Variable),
+  Reference(Reference),
   Color(Color),
   ColorSet(ColorSet),
+  /// Only valid as the value of a variable. Expanded into one variable per step before resolution.
+  Scale(Scale),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
 pub enum ColorSetValue {
   Variable(Variable),
+  Reference(Reference),
   Color(Color),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Variable {
   pub identifier: String,
   pub opacity: f32,
 }
 
+/// A reference to another declaration by its dotted path, e.g. `&NumericInput.DoneKey.Background`.
+/// The path is looked up relative to the enclosing ruleset first, then relative to each ruleset
+/// further out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reference {
+  pub path: String,
+  pub opacity: f32,
+}
+
+/// A family of colors generated from a base color by varying its OKLCH lightness, e.g.
+/// `$blue: scale(#1E6FFF, steps: 9, from: 10%, to: 90%)` declares `$blue100` to `$blue900`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scale {
+  pub base: Color,
+  pub steps: u32,
+  /// How far the lightest step is from white towards black, between 0 and 1.
+  pub from: f32,
+  /// How far the darkest step is from white towards black, between 0 and 1.
+  pub to: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorSet {
   pub light: ColorSetValue,
   pub dark: ColorSetValue,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Declaration<T> {
   pub identifier: String,
   pub value: T,
+  /// The lines of the doc comments (`///`) preceding the declaration.
+  pub doc: Vec<String>,
+  pub annotations: Vec<Annotation>,
+  /// The span from the identifier to the end of the value.
+  pub span: Span,
+}
+
+/// An annotation preceding a declaration, e.g. `@deprecated("Use Text.Primary")`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
+pub enum Annotation {
+  Deprecated {
+    message: Option<String>,
+  },
+  /// The declaration has been renamed to the declaration at the given dotted path.
+  Renamed {
+    identifier: String,
+  },
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RuleSet {
   pub identifier: String,
   pub items: Vec<RuleSetItem>,
+  /// The lines of the doc comments (`///`) preceding the ruleset.
+  pub doc: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
 pub enum RuleSetItem {
+  /// A variable only visible inside the ruleset and its nested rulesets.
+  Variable(Declaration<Value>),
   RuleSet(RuleSet),
   Declaration(Declaration<Value>),
+  Extend(Extend),
 }
 
-impl Into<Option<Color>> for Value {
-  fn into(self) -> Option<Color> {
-    match self {
-      Self::Color(c) => Some(c),
+/// Inherits the items of another ruleset, e.g. `@extend Button`. Items of the extending ruleset
+/// override inherited items with the same identifier.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extend {
+  /// The dotted path of the extended ruleset, looked up like the path of a `Reference`.
+  pub path: String,
+  pub span: Span,
+}
+
+impl From<Value> for Option<Color> {
+  fn from(val: Value) -> Self {
+    match val {
+      Value::Color(c) => Some(c),
       _ => None,
     }
   }
 }
 
-impl Into<Value> for ColorSetValue {
-  fn into(self) -> Value {
-    match self {
-      Self::Color(c) => Value::Color(c),
-      Self::Variable(v) => Value::Variable(v),
+impl From<ColorSetValue> for Value {
+  fn from(val: ColorSetValue) -> Self {
+    match val {
+      ColorSetValue::Color(c) => Value::Color(c),
+      ColorSetValue::Variable(v) => Value::Variable(v),
+      ColorSetValue::Reference(r) => Value::Reference(r),
     }
   }
 }
@@ -82,10 +202,41 @@ impl Into<Value> for ColorSetValue {
 impl Variable {
   pub fn resolve_against(&self, color: &Color) -> Color {
     Color {
-      r: color.r,
-      g: color.g,
-      b: color.b,
       a: color.a * self.opacity,
+      ..color.clone()
     }
   }
 }
+
+impl Span {
+  /// Returns the 1-based line and column of the start of the span in `source`.
+  pub fn line_column(&self, source: &str) -> (usize, usize) {
+    let before = &source[..self.start.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+  }
+}
+
+impl ContrastLevel {
+  /// The minimum contrast ratio required by the level.
+  pub fn minimum_ratio(&self) -> f32 {
+    match self {
+      Self::AA => 4.5,
+      Self::AALarge => 3.0,
+      Self::AAA => 7.0,
+      Self::AAALarge => 4.5,
+    }
+  }
+}
+
+impl fmt::Display for ContrastLevel {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::AA => "AA",
+      Self::AALarge => "AA-large",
+      Self::AAA => "AAA",
+      Self::AAALarge => "AAA-large",
+    })
+  }
+}
@@ -0,0 +1,502 @@
+//! Conversions between sRGB and the perceptual OKLab, OKLCH, CIE Lab and CIE LCH color spaces. The
+//! CIE spaces use a D50 white point like their CSS counterparts `lab()` and `lch()`.
+//!
+//! Also provides the color math shared by the tools, like contrast ratios and color differences.
+
+use super::ast::Color;
+
+/// A color in the OKLab color space. `l` ranges from 0 (black) to 1 (white).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+  pub l: f64,
+  pub a: f64,
+  pub b: f64,
+}
+
+/// A color in the OKLCH color space, the polar form of OKLab. `h` is the hue angle in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+  pub l: f64,
+  pub c: f64,
+  pub h: f64,
+}
+
+/// A color in the CIE Lab color space. `l` ranges from 0 (black) to 100 (white).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+  pub l: f64,
+  pub a: f64,
+  pub b: f64,
+}
+
+/// A color in the CIE LCH color space, the polar form of CIE Lab. `h` is the hue angle in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lch {
+  pub l: f64,
+  pub c: f64,
+  pub h: f64,
+}
+
+impl Oklab {
+  pub fn from_color(color: &Color) -> Self {
+    Self::from_linear_srgb([decode(color.r), decode(color.g), decode(color.b)])
+  }
+
+  pub fn from_linear_srgb([r, g, b]: [f64; 3]) -> Self {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    Oklab {
+      l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+      a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+      b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    }
+  }
+
+  /// Returns the linear sRGB components, which lie outside of 0...1 for colors outside of the sRGB
+  /// gamut.
+  pub fn to_linear_srgb(&self) -> [f64; 3] {
+    let l = (self.l + 0.3963377774 * self.a + 0.2158037573 * self.b).powi(3);
+    let m = (self.l - 0.1055613458 * self.a - 0.0638541728 * self.b).powi(3);
+    let s = (self.l - 0.0894841775 * self.a - 1.2914855480 * self.b).powi(3);
+
+    [
+      4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+      -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+      -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+    ]
+  }
+
+  /// Converts the color into sRGB like `Oklch::to_color`.
+  pub fn to_color(&self, alpha: f32) -> (Color, bool) {
+    Oklch::from(*self).to_color(alpha)
+  }
+}
+
+impl Oklch {
+  pub fn from_color(color: &Color) -> Self {
+    Self::from(Oklab::from_color(color))
+  }
+
+  /// Converts the color into sRGB with the given alpha. Colors outside of the sRGB gamut are mapped
+  /// into it by reducing their chroma while keeping their lightness and hue, like CSS does. Colors
+  /// within a just noticeable difference of the gamut are clipped instead and don't count as
+  /// mapped. Returns the color along with whether it had to be mapped.
+  pub fn to_color(&self, alpha: f32) -> (Color, bool) {
+    let l = self.l.clamp(0.0, 1.0);
+    let linear = |c: f64| Oklab::from(Oklch { l, c, h: self.h }).to_linear_srgb();
+    let is_clipped = l != self.l;
+    // Mapped colors remember where they came from, see `Color::linear`.
+    let mapped = |color: Color| Color {
+      linear: Some(
+        Oklab::from(Oklch {
+          c: self.c.max(0.0),
+          ..*self
+        })
+        .to_linear_srgb(),
+      ),
+      ..color
+    };
+
+    let mut chroma = self.c.max(0.0);
+    if is_in_gamut(linear(chroma)) || is_close_to_gamut(linear(chroma)) {
+      let color = encode_color(linear(chroma), alpha);
+      return if is_clipped {
+        (mapped(color), true)
+      } else {
+        (color, false)
+      };
+    }
+
+    let mut low = 0.0;
+    for _ in 0..24 {
+      let mid = (low + chroma) / 2.0;
+      if is_in_gamut(linear(mid)) {
+        low = mid;
+      } else {
+        chroma = mid;
+      }
+    }
+    (mapped(encode_color(linear(low), alpha)), true)
+  }
+}
+
+impl Lab {
+  pub fn from_color(color: &Color) -> Self {
+    let linear = [decode(color.r), decode(color.g), decode(color.b)];
+    let [x, y, z] = multiply(&D65_TO_D50, multiply(&LINEAR_SRGB_TO_XYZ, linear));
+    let f = |t: f64| {
+      if t > EPSILON {
+        t.cbrt()
+      } else {
+        (KAPPA * t + 16.0) / 116.0
+      }
+    };
+    let (fx, fy, fz) = (f(x / D50[0]), f(y / D50[1]), f(z / D50[2]));
+
+    Lab {
+      l: 116.0 * fy - 16.0,
+      a: 500.0 * (fx - fy),
+      b: 200.0 * (fy - fz),
+    }
+  }
+
+  /// Returns the linear sRGB components, which lie outside of 0...1 for colors outside of the sRGB
+  /// gamut.
+  pub fn to_linear_srgb(&self) -> [f64; 3] {
+    let fy = (self.l + 16.0) / 116.0;
+    let fx = self.a / 500.0 + fy;
+    let fz = fy - self.b / 200.0;
+    let f_inv = |f: f64| {
+      if f.powi(3) > EPSILON {
+        f.powi(3)
+      } else {
+        (116.0 * f - 16.0) / KAPPA
+      }
+    };
+    let y = if self.l > KAPPA * EPSILON {
+      fy.powi(3)
+    } else {
+      self.l / KAPPA
+    };
+    let xyz = [f_inv(fx) * D50[0], y * D50[1], f_inv(fz) * D50[2]];
+
+    multiply(&XYZ_TO_LINEAR_SRGB, multiply(&D50_TO_D65, xyz))
+  }
+
+  /// Converts the color into sRGB with the given alpha. Colors outside of the sRGB gamut are mapped
+  /// into it like `Oklch::to_color`. Returns the color along with whether it had to be mapped.
+  pub fn to_color(&self, alpha: f32) -> (Color, bool) {
+    let linear = self.to_linear_srgb();
+    if is_in_gamut(linear) {
+      return (encode_color(linear, alpha), false);
+    }
+    Oklch::from(Oklab::from_linear_srgb(linear)).to_color(alpha)
+  }
+}
+
+impl Lch {
+  pub fn from_color(color: &Color) -> Self {
+    Self::from(Lab::from_color(color))
+  }
+
+  /// Converts the color into sRGB like `Lab::to_color`.
+  pub fn to_color(&self, alpha: f32) -> (Color, bool) {
+    Lab::from(*self).to_color(alpha)
+  }
+}
+
+impl From<Lab> for Lch {
+  fn from(lab: Lab) -> Self {
+    let (c, h) = polar(lab.a, lab.b);
+    Lch { l: lab.l, c, h }
+  }
+}
+
+impl From<Lch> for Lab {
+  fn from(lch: Lch) -> Self {
+    let (a, b) = cartesian(lch.c, lch.h);
+    Lab { l: lch.l, a, b }
+  }
+}
+
+impl From<Oklab> for Oklch {
+  fn from(lab: Oklab) -> Self {
+    let (c, h) = polar(lab.a, lab.b);
+    Oklch { l: lab.l, c, h }
+  }
+}
+
+impl From<Oklch> for Oklab {
+  fn from(lch: Oklch) -> Self {
+    let (a, b) = cartesian(lch.c, lch.h);
+    Oklab { l: lch.l, a, b }
+  }
+}
+
+/// Returns the linear light components of the 8-bit sRGB `color`.
+pub fn linear_srgb_components(color: &Color) -> [f64; 3] {
+  [decode(color.r), decode(color.g), decode(color.b)]
+}
+
+/// Converts `color` into the components of the Display P3 color space. Colors which have been
+/// mapped into sRGB are converted from their original components, see `Color::linear`.
+/// Components outside of the Display P3 gamut are clamped to 0...1, see `is_in_display_p3_gamut`.
+pub fn display_p3_components(color: &Color) -> [f64; 3] {
+  // Display P3 shares the transfer function of sRGB.
+  linear_display_p3(color).map(|c| gamma(c.clamp(0.0, 1.0)))
+}
+
+/// Whether `color` can be represented in Display P3 without clamping its components. Only colors
+/// which have been mapped into sRGB can lie outside of Display P3.
+pub fn is_in_display_p3_gamut(color: &Color) -> bool {
+  is_in_gamut(linear_display_p3(color))
+}
+
+fn linear_display_p3(color: &Color) -> [f64; 3] {
+  let linear = color
+    .linear
+    .unwrap_or_else(|| linear_srgb_components(color));
+  multiply(
+    &XYZ_TO_LINEAR_DISPLAY_P3,
+    multiply(&LINEAR_SRGB_TO_XYZ, linear),
+  )
+}
+
+/// Opaque white, the background of the light appearance.
+pub const WHITE: Color = Color {
+  r: 255,
+  g: 255,
+  b: 255,
+  a: 1.0,
+  linear: None,
+};
+
+/// Opaque black, the background of the dark appearance.
+pub const BLACK: Color = Color {
+  r: 0,
+  g: 0,
+  b: 0,
+  a: 1.0,
+  linear: None,
+};
+
+/// Returns the relative luminance of `color` as defined by WCAG 2, ignoring its alpha.
+pub fn relative_luminance(color: &Color) -> f32 {
+  let linear = |channel: u8| {
+    let c = f32::from(channel) / 255.0;
+    if c <= 0.03928 {
+      c / 12.92
+    } else {
+      ((c + 0.055) / 1.055).powf(2.4)
+    }
+  };
+
+  0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+}
+
+/// Returns the contrast ratio between two opaque colors, ranging from 1 to 21.
+pub fn contrast_ratio(a: &Color, b: &Color) -> f32 {
+  let (a, b) = (relative_luminance(a), relative_luminance(b));
+  (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Composites `foreground` over `background` according to their alpha values.
+pub fn composite(foreground: &Color, background: &Color) -> Color {
+  let a = foreground.a + background.a * (1.0 - foreground.a);
+  if a == 0.0 {
+    return Color {
+      r: 0,
+      g: 0,
+      b: 0,
+      a: 0.0,
+      linear: None,
+    };
+  }
+
+  let channel = |f: u8, b: u8| {
+    let value =
+      (f32::from(f) * foreground.a + f32::from(b) * background.a * (1.0 - foreground.a)) / a;
+    value.round().clamp(0.0, 255.0) as u8
+  };
+
+  Color {
+    r: channel(foreground.r, background.r),
+    g: channel(foreground.g, background.g),
+    b: channel(foreground.b, background.b),
+    a,
+    linear: None,
+  }
+}
+
+/// Returns the CIE76 color difference (ΔE) between two colors, ignoring their alpha.
+pub fn color_difference(a: &Color, b: &Color) -> f32 {
+  let (a, b) = (d65_lab(a), d65_lab(b));
+  ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+}
+
+/// Converts an sRGB color into CIELAB, using the D65 white point of sRGB rather than the D50 white
+/// point of `Lab`.
+fn d65_lab(color: &Color) -> (f32, f32, f32) {
+  let linear = |channel: u8| decode(channel) as f32;
+  let (r, g, b) = (linear(color.r), linear(color.g), linear(color.b));
+
+  let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+  let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+  let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+  let f = |t: f32| {
+    if t > 216.0 / 24389.0 {
+      t.cbrt()
+    } else {
+      (24389.0 / 27.0 * t + 16.0) / 116.0
+    }
+  };
+  let (fx, fy, fz) = (f(x), f(y), f(z));
+
+  (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+/// Compares two colors, allowing for rounding errors in their alpha.
+pub fn is_same_color(a: &Color, b: &Color) -> bool {
+  a.r == b.r && a.g == b.g && a.b == b.b && (a.a - b.a).abs() < 0.001
+}
+
+/// Formats `color` in the notation of the stylesheet, e.g. `#151618` or `#000000 50%`.
+pub fn hex_string(color: &Color) -> String {
+  let hex = format!("#{:02X}{:02X}{:02X}", color.r, color.g, color.b);
+  if (color.a - 1.0).abs() < 0.001 {
+    hex
+  } else {
+    format!("{} {}%", hex, (color.a * 100.0).round())
+  }
+}
+
+/// Returns the chroma and hue in degrees of the opponent components `a` and `b`.
+fn polar(a: f64, b: f64) -> (f64, f64) {
+  let h = b.atan2(a).to_degrees();
+  ((a * a + b * b).sqrt(), if h < 0.0 { h + 360.0 } else { h })
+}
+
+/// Returns the opponent components `a` and `b` of the chroma and hue in degrees.
+fn cartesian(c: f64, h: f64) -> (f64, f64) {
+  let h = h.to_radians();
+  (c * h.cos(), c * h.sin())
+}
+
+const EPSILON: f64 = 216.0 / 24389.0;
+const KAPPA: f64 = 24389.0 / 27.0;
+
+/// The XYZ coordinates of the D50 white point.
+const D50: [f64; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+const LINEAR_SRGB_TO_XYZ: [[f64; 3]; 3] = [
+  [0.41239079926595934, 0.357584339383878, 0.1804807884018343],
+  [0.21263900587151027, 0.715168678767756, 0.07219231536073371],
+  [0.01933081871559182, 0.11919477979462598, 0.9505321522496607],
+];
+
+const XYZ_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+  [3.2409699419045226, -1.537383177570094, -0.4986107602930034],
+  [-0.9692436362808796, 1.8759675015077202, 0.04155505740717559],
+  [
+    0.05563007969699366,
+    -0.20397695888897652,
+    1.0569715142428786,
+  ],
+];
+
+const XYZ_TO_LINEAR_DISPLAY_P3: [[f64; 3]; 3] = [
+  [2.493496911941425, -0.9313836179191239, -0.40271078445071684],
+  [
+    -0.8294889695615747,
+    1.7626640603183463,
+    0.023624685841943577,
+  ],
+  [
+    0.03584583024378447,
+    -0.07617238926804182,
+    0.9568845240076872,
+  ],
+];
+
+/// The Bradford chromatic adaptation from the D65 to the D50 white point.
+const D65_TO_D50: [[f64; 3]; 3] = [
+  [
+    1.0479298208405488,
+    0.022946793341019088,
+    -0.05019222954313557,
+  ],
+  [
+    0.029627815688159344,
+    0.990434484573249,
+    -0.01707382502938514,
+  ],
+  [
+    -0.009243058152591178,
+    0.015055144896577895,
+    0.7518742899580008,
+  ],
+];
+
+/// The Bradford chromatic adaptation from the D50 to the D65 white point.
+const D50_TO_D65: [[f64; 3]; 3] = [
+  [
+    0.9554734527042182,
+    -0.023098536874261423,
+    0.0632593086610217,
+  ],
+  [
+    -0.028369706963208136,
+    1.0099954580058226,
+    0.021041398966943008,
+  ],
+  [
+    0.012314001688319899,
+    -0.020507696433477912,
+    1.3303659366080753,
+  ],
+];
+
+fn multiply(matrix: &[[f64; 3]; 3], vector: [f64; 3]) -> [f64; 3] {
+  matrix.map(|row| row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2])
+}
+
+/// Tolerates rounding errors of the conversions at the edges of the gamut.
+const GAMUT_EPSILON: f64 = 0.0001;
+
+fn is_in_gamut(linear: [f64; 3]) -> bool {
+  linear
+    .iter()
+    .all(|c| (-GAMUT_EPSILON..=1.0 + GAMUT_EPSILON).contains(c))
+}
+
+/// The color difference in OKLab below which colors are considered the same, as defined by CSS for
+/// gamut mapping.
+const JUST_NOTICEABLE_DIFFERENCE: f64 = 0.02;
+
+/// Whether clipping the out of gamut components changes the color less than noticeably.
+fn is_close_to_gamut(linear: [f64; 3]) -> bool {
+  let original = Oklab::from_linear_srgb(linear);
+  let clipped = Oklab::from_linear_srgb(linear.map(|c| c.clamp(0.0, 1.0)));
+  let difference = ((original.l - clipped.l).powi(2)
+    + (original.a - clipped.a).powi(2)
+    + (original.b - clipped.b).powi(2))
+  .sqrt();
+  difference < JUST_NOTICEABLE_DIFFERENCE
+}
+
+fn encode_color([r, g, b]: [f64; 3], alpha: f32) -> Color {
+  Color {
+    r: encode(r),
+    g: encode(g),
+    b: encode(b),
+    a: alpha,
+    linear: None,
+  }
+}
+
+/// Converts an 8-bit sRGB channel into linear light.
+fn decode(channel: u8) -> f64 {
+  let c = f64::from(channel) / 255.0;
+  if c <= 0.04045 {
+    c / 12.92
+  } else {
+    ((c + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+/// Converts a linear light channel into 8-bit sRGB.
+fn encode(linear: f64) -> u8 {
+  (gamma(linear.clamp(0.0, 1.0)) * 255.0).round() as u8
+}
+
+/// Applies the sRGB transfer function to a linear light channel.
+fn gamma(linear: f64) -> f64 {
+  if linear <= 0.0031308 {
+    linear * 12.92
+  } else {
+    1.055 * linear.powf(1.0 / 2.4) - 0.055
+  }
+}
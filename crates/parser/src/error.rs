@@ -1,3 +1,4 @@
+use super::ast::Span;
 use std::io;
 use std::str::Utf8Error;
 
@@ -6,11 +7,47 @@ pub enum Error {
   #[error("{message}")]
   InvalidColorSetDeclaration { message: String },
   #[error("Error: {message}")]
-  ParseError { message: String },
+  ParseError {
+    message: String,
+    /// A single-line description of the error, e.g. `expected ':' in Declaration`.
+    summary: String,
+    /// The location in the stylesheet where parsing failed.
+    span: Span,
+  },
   #[error("{message}")]
-  ResolveError { message: String },
+  ResolveError {
+    message: String,
+    /// The declaration or `@extend` the error occurred at, if known.
+    span: Option<Span>,
+  },
   #[error(transparent)]
   Io(#[from] io::Error),
   #[error(transparent)]
   Utf8(#[from] Utf8Error),
 }
+
+impl Error {
+  /// The location in the stylesheet the error refers to, if known.
+  pub fn span(&self) -> Option<Span> {
+    match self {
+      Self::ParseError { span, .. } => Some(*span),
+      Self::ResolveError { span, .. } => *span,
+      _ => None,
+    }
+  }
+
+  /// Attaches `span` to a resolve error which isn't located yet. Errors are located by the
+  /// innermost declaration that knows about them.
+  pub(crate) fn located_at(self, span: Span) -> Self {
+    match self {
+      Self::ResolveError {
+        message,
+        span: None,
+      } => Self::ResolveError {
+        message,
+        span: Some(span),
+      },
+      e => e,
+    }
+  }
+}
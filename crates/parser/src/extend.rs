@@ -0,0 +1,182 @@
+//! Replaces `@extend` with copies of the inherited items, so that inherited declarations are
+//! resolved and generated like declarations of the extending ruleset.
+
+use super::ast::{Document, DocumentItem, RuleSet, RuleSetItem};
+use super::var_context::{Items, Target};
+use super::Error;
+use std::collections::HashMap;
+
+/// Returns `doc` with the `@extend` items of all rulesets replaced by the items they inherit.
+pub fn expand_extends(doc: &Document) -> Result<Document, Error> {
+  let mut expander = Expander {
+    doc,
+    expanded: HashMap::new(),
+    stack: vec![],
+  };
+
+  let items = doc
+    .items
+    .iter()
+    .map(|item| match item {
+      DocumentItem::RuleSet(ruleset) => expander
+        .expand(vec![ruleset.identifier.clone()], ruleset)
+        .map(DocumentItem::RuleSet),
+      item => Ok(item.clone()),
+    })
+    .collect::<Result<_, _>>()?;
+
+  Ok(Document { items })
+}
+
+struct Expander<'a> {
+  doc: &'a Document,
+  /// The rulesets which have been expanded already by their path.
+  expanded: HashMap<Vec<String>, RuleSet>,
+  /// The dotted paths of the rulesets currently being expanded, to detect circular extends.
+  stack: Vec<String>,
+}
+
+impl<'a> Expander<'a> {
+  fn expand(&mut self, path: Vec<String>, ruleset: &'a RuleSet) -> Result<RuleSet, Error> {
+    if let Some(expanded) = self.expanded.get(&path) {
+      return Ok(expanded.clone());
+    }
+
+    let dotted = path.join(".");
+    if let Some(idx) = self.stack.iter().position(|entry| *entry == dotted) {
+      let cycle: Vec<&str> = self.stack[idx..]
+        .iter()
+        .map(String::as_str)
+        .chain([dotted.as_str()])
+        .collect();
+      return Err(Error::ResolveError {
+        message: format!("Circular @extend: {}.", cycle.join(" → ")),
+        span: None,
+      });
+    }
+    self.stack.push(dotted.clone());
+
+    let mut inherited = vec![];
+    let mut items = vec![];
+    for item in &ruleset.items {
+      match item {
+        RuleSetItem::Extend(extend) => {
+          let locate = |e: Error| e.located_at(extend.span);
+          let (base_path, base) = self
+            .find_ruleset(&path[..path.len() - 1], &extend.path, &dotted)
+            .map_err(locate)?;
+          let base = self.expand(base_path, base).map_err(locate)?;
+          inherited = overlay(inherited, base.items);
+        }
+        RuleSetItem::RuleSet(nested) => {
+          let mut nested_path = path.clone();
+          nested_path.push(nested.identifier.clone());
+          items.push(RuleSetItem::RuleSet(self.expand(nested_path, nested)?));
+        }
+        item => items.push(item.clone()),
+      }
+    }
+    self.stack.pop();
+
+    let expanded = RuleSet {
+      identifier: ruleset.identifier.clone(),
+      items: overlay(inherited, items),
+      doc: ruleset.doc.clone(),
+    };
+    self.expanded.insert(path, expanded.clone());
+    Ok(expanded)
+  }
+
+  /// Looks up the ruleset at the dotted `path` relative to the ruleset at `scope` and each ruleset
+  /// further out. Returns the full path of the ruleset along with the ruleset.
+  fn find_ruleset(
+    &self,
+    scope: &[String],
+    path: &str,
+    extending: &str,
+  ) -> Result<(Vec<String>, &'a RuleSet), Error> {
+    for depth in (0..=scope.len()).rev() {
+      if let Some((full_path, target)) = self.find(&scope[..depth], path) {
+        return match target {
+          Target::RuleSet(ruleset) => Ok((full_path, ruleset)),
+          Target::Declaration(_) => Err(Error::ResolveError {
+            message: format!(
+              "@extend {} in {} refers to a declaration. Only rulesets can be extended.",
+              path, extending
+            ),
+            span: None,
+          }),
+        };
+      }
+    }
+
+    Err(Error::ResolveError {
+      message: format!("Could not find ruleset {} extended by {}.", path, extending),
+      span: None,
+    })
+  }
+
+  /// Returns the item at the dotted `path` relative to the ruleset at `scope`.
+  fn find(&self, scope: &[String], path: &str) -> Option<(Vec<String>, Target<'a>)> {
+    let mut items = Items::Document(&self.doc.items);
+    for identifier in scope {
+      match items.child(identifier)? {
+        Target::RuleSet(ruleset) => items = Items::RuleSet(&ruleset.items),
+        Target::Declaration(_) => return None,
+      }
+    }
+
+    let mut segments = path.split('.');
+    let mut target = items.child(segments.next()?)?;
+    for identifier in segments {
+      match target {
+        Target::RuleSet(ruleset) => target = Items::RuleSet(&ruleset.items).child(identifier)?,
+        Target::Declaration(_) => return None,
+      }
+    }
+
+    let mut full_path = scope.to_vec();
+    full_path.extend(path.split('.').map(str::to_string));
+    Some((full_path, target))
+  }
+}
+
+/// Returns the items of `base` which are not overridden by an item with the same identifier in
+/// `items`, followed by `items`. Rulesets present in both are merged the same way.
+fn overlay(mut base: Vec<RuleSetItem>, items: Vec<RuleSetItem>) -> Vec<RuleSetItem> {
+  let mut overlaid = vec![];
+
+  for item in items {
+    let overridden = base
+      .iter()
+      .position(|inherited| key(inherited) == key(&item))
+      .map(|idx| base.remove(idx));
+
+    overlaid.push(match (overridden, item) {
+      (Some(RuleSetItem::RuleSet(inherited)), RuleSetItem::RuleSet(ruleset)) => {
+        RuleSetItem::RuleSet(RuleSet {
+          items: overlay(inherited.items, ruleset.items),
+          doc: if ruleset.doc.is_empty() {
+            inherited.doc
+          } else {
+            ruleset.doc
+          },
+          identifier: ruleset.identifier,
+        })
+      }
+      (_, item) => item,
+    });
+  }
+
+  base.into_iter().chain(overlaid).collect()
+}
+
+/// Identifies the items which override each other. Variables live in their own namespace.
+fn key(item: &RuleSetItem) -> Option<(bool, &str)> {
+  match item {
+    RuleSetItem::Variable(variable) => Some((true, &variable.identifier)),
+    RuleSetItem::Declaration(decl) => Some((false, &decl.identifier)),
+    RuleSetItem::RuleSet(ruleset) => Some((false, &ruleset.identifier)),
+    RuleSetItem::Extend(_) => None,
+  }
+}
@@ -0,0 +1,122 @@
+//! Finds colors written in a perceptual color space, or derived in one by a scale, which are
+//! outside of the gamut of the color space they are generated in.
+
+use super::ast::{Declaration, Document, DocumentItem, RuleSetItem, Span, Value};
+use super::color_space::is_in_display_p3_gamut;
+use super::parser::color_function;
+use nom::error::VerboseError;
+
+/// A problem with the stylesheet which doesn't prevent generating it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+  pub span: Span,
+  pub message: String,
+}
+
+/// The colors the generated files can represent, ordered from narrow to wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Gamut {
+  /// Colors are generated as they have been mapped into sRGB while parsing.
+  #[default]
+  SRGB,
+  /// Colors are converted into Display P3 from their original components.
+  DisplayP3,
+}
+
+/// The color functions which may describe colors outside of the sRGB gamut.
+const COLOR_FUNCTIONS: [&str; 3] = ["oklch(", "oklab(", "lch("];
+
+/// Returns a warning for each color of `doc` which is outside of `gamut`, so that it is generated
+/// differently than written. `source` is the text `doc` has been parsed from.
+pub fn gamut_warnings(doc: &Document, source: &str, gamut: Gamut) -> Vec<Warning> {
+  let mut warnings = vec![];
+
+  for item in &doc.items {
+    match item {
+      DocumentItem::Variable(decl) | DocumentItem::Declaration(decl) => {
+        check_declaration(decl, source, gamut, &mut warnings)
+      }
+      DocumentItem::RuleSet(ruleset) => check_items(&ruleset.items, source, gamut, &mut warnings),
+      DocumentItem::Contrast(_) => {}
+    }
+  }
+
+  warnings.sort_by_key(|warning| warning.span.start);
+  warnings
+}
+
+fn check_items(items: &[RuleSetItem], source: &str, gamut: Gamut, warnings: &mut Vec<Warning>) {
+  for item in items {
+    match item {
+      RuleSetItem::Variable(decl) | RuleSetItem::Declaration(decl) => {
+        check_declaration(decl, source, gamut, warnings)
+      }
+      RuleSetItem::RuleSet(ruleset) => check_items(&ruleset.items, source, gamut, warnings),
+      RuleSetItem::Extend(_) => {}
+    }
+  }
+}
+
+/// Parses the color functions within the source of `decl` once more, since the parsed colors don't
+/// remember where they have been written.
+fn check_declaration(
+  decl: &Declaration<Value>,
+  source: &str,
+  gamut: Gamut,
+  warnings: &mut Vec<Warning>,
+) {
+  // Scale steps are generated in sRGB in either gamut, so mapping them is always reported.
+  if let Value::Scale(scale) = &decl.value {
+    for (idx, (color, mapped)) in scale.steps().into_iter().enumerate() {
+      if mapped {
+        warnings.push(Warning {
+          span: decl.span,
+          message: format!(
+            "${}{} is outside of the sRGB gamut and has been mapped to #{:02X}{:02X}{:02X}.",
+            decl.identifier,
+            (idx + 1) * 100,
+            color.r,
+            color.g,
+            color.b
+          ),
+        });
+      }
+    }
+  }
+
+  let text = match source.get(decl.span.start..decl.span.end) {
+    Some(text) => text,
+    None => return,
+  };
+
+  for (offset, _) in text.char_indices() {
+    let rest = &text[offset..];
+    let is_function_start = COLOR_FUNCTIONS.iter().any(|name| rest.starts_with(name))
+      && !text[..offset].ends_with(|c: char| c.is_ascii_alphanumeric());
+    if !is_function_start {
+      continue;
+    }
+
+    if let Ok((remaining, (color, true))) = color_function::<VerboseError<&str>>(rest) {
+      let function = &rest[..rest.len() - remaining.len()];
+      let message = match gamut {
+        Gamut::SRGB => format!(
+          "{} is outside of the sRGB gamut and has been mapped to #{:02X}{:02X}{:02X}.",
+          function, color.r, color.g, color.b
+        ),
+        Gamut::DisplayP3 if !is_in_display_p3_gamut(&color) => format!(
+          "{} is outside of the Display P3 gamut and has been clamped.",
+          function
+        ),
+        Gamut::DisplayP3 => continue,
+      };
+      warnings.push(Warning {
+        span: Span {
+          start: decl.span.start + offset,
+          end: decl.span.start + offset + function.len(),
+        },
+        message,
+      });
+    }
+  }
+}
@@ -1,8 +1,23 @@
 pub mod ast;
+pub mod color_space;
 mod error;
+mod extend;
+mod gamut;
+mod naming;
 mod parser;
+pub mod resolved;
+mod scale;
 mod var_context;
 
 pub use self::error::Error;
+pub use self::extend::expand_extends;
+pub use self::gamut::{gamut_warnings, Gamut, Warning};
+pub use self::naming::AssetNaming;
 pub use self::parser::{parse_document, parse_document_from_file};
+pub use self::scale::expand_scales;
 pub use self::var_context::{ResolvedColorSet, ResolvedVariable, VarContext};
+
+/// The version of the JSON schema of the serialized `ast` and `resolved` types. Bumped whenever a
+/// change to these types alters their serialized form.
+#[cfg(feature = "serde")]
+pub const SCHEMA_VERSION: u32 = 1;
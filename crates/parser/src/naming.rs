@@ -0,0 +1,56 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Describes how the path of a declaration is turned into the name of its color asset.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AssetNaming {
+  /// `NumericInputNumericKeyBackground`
+  #[default]
+  Concatenated,
+  /// `NumericInput/NumericKey/Background`, backed by folders that provide a namespace.
+  Namespaced,
+  /// `NumericInput.NumericKey.Background`
+  Dotted,
+}
+
+impl AssetNaming {
+  /// Appends `child` to the asset name `parent`, which is empty for top-level declarations.
+  pub fn join(&self, parent: &str, child: &str) -> String {
+    if parent.is_empty() {
+      return child.to_string();
+    }
+    match self {
+      Self::Concatenated => format!("{}{}", parent, child),
+      Self::Namespaced => format!("{}/{}", parent, child),
+      Self::Dotted => format!("{}.{}", parent, child),
+    }
+  }
+
+  /// Whether ruleset folders in the asset catalog need to provide a namespace.
+  pub fn provides_namespace(&self) -> bool {
+    *self == Self::Namespaced
+  }
+}
+
+impl FromStr for AssetNaming {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<AssetNaming, ()> {
+    match s.to_lowercase().as_ref() {
+      "concatenated" => Ok(AssetNaming::Concatenated),
+      "namespaced" => Ok(AssetNaming::Namespaced),
+      "dotted" => Ok(AssetNaming::Dotted),
+      _ => Err(()),
+    }
+  }
+}
+
+impl fmt::Display for AssetNaming {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Concatenated => "concatenated",
+      Self::Namespaced => "namespaced",
+      Self::Dotted => "dotted",
+    })
+  }
+}
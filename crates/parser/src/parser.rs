@@ -4,25 +4,27 @@ use std::num::ParseIntError;
 use std::path::Path;
 use std::str;
 
+use crate::color_space::{Lch, Oklab, Oklch};
 use crate::error::Error;
 use nom::branch::alt;
-use nom::bytes::complete::{tag, take_while_m_n};
+use nom::bytes::complete::{tag, take_while, take_while_m_n};
 use nom::character::complete::{
-  alphanumeric1, char, digit1, multispace0, newline, not_line_ending, space0, space1,
+  char, digit1, multispace0, multispace1, newline, not_line_ending, satisfy, space0, space1,
 };
-use nom::combinator::{all_consuming, cut, map, map_res, opt};
+use nom::combinator::{all_consuming, cut, map, map_res, not, opt, recognize, verify};
 use nom::error::{
   context, convert_error, ContextError, FromExternalError, ParseError, VerboseError,
+  VerboseErrorKind,
 };
-use nom::multi::{many0, separated_list0};
-use nom::number::complete::float;
-use nom::sequence::{delimited, preceded, separated_pair, terminated, tuple};
+use nom::multi::{many0, separated_list0, separated_list1};
+use nom::number::complete::{double, float};
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
 use nom::Err;
 use nom::IResult;
 
 use super::ast::{
-  Color, ColorSet, ColorSetValue, Declaration, Document, DocumentItem, RuleSet, RuleSetItem, Value,
-  Variable,
+  Annotation, Color, ColorSet, ColorSetValue, ContrastLevel, ContrastRequirement, Declaration,
+  Document, DocumentItem, Extend, Reference, RuleSet, RuleSetItem, Scale, Span, Value, Variable,
 };
 
 fn parse_hex_value(input: &str) -> Result<u32, std::num::ParseIntError> {
@@ -53,36 +55,71 @@ fn colorset_from_declarations(
 
 pub fn parse_document(input: String) -> Result<Document, Error> {
   let mut modified_input = input;
-  modified_input.push_str("\n");
+  modified_input.push('\n');
 
   let result: IResult<&str, Document, VerboseError<&str>> = map(
     all_consuming(delimited(
-      multiline_whitespace,
+      multispace0,
       separated_list0(
         line_delimiter,
         alt((
           map(variable, DocumentItem::Variable),
+          map(contrast_requirement, DocumentItem::Contrast),
           map(ruleset, DocumentItem::RuleSet),
-          map(declaration(value), DocumentItem::Declaration),
+          map(documented_declaration, DocumentItem::Declaration),
         )),
       ),
       multiline_whitespace,
     )),
-    |res| Document { items: res },
+    |res| Document {
+      items: expand_dotted_identifiers(locate_spans(res, modified_input.len())),
+    },
   )(&modified_input);
 
   match result {
     Ok((_, doc)) => Ok(doc),
-    Err(Err::Error(e)) | Err(Err::Failure(e)) => Err(Error::ParseError {
-      message: convert_error(modified_input.as_ref(), e).replace("'\n'", "'\\n'"),
-    }),
-    _ => {
-      eprintln!("An unknown error occurred.");
-      std::process::exit(0x0100)
+    Err(Err::Error(e)) | Err(Err::Failure(e)) => {
+      let start = e
+        .errors
+        .first()
+        .map_or(0, |(remaining, _)| modified_input.len() - remaining.len());
+      Err(Error::ParseError {
+        summary: summarize_error(&e),
+        span: Span { start, end: start },
+        message: convert_error(modified_input.as_ref(), e).replace("'\n'", "'\\n'"),
+      })
+    }
+    Err(Err::Incomplete(_)) => {
+      // The parsers operate on complete input, so this only happens if one of them is misused.
+      let end = modified_input.len();
+      Err(Error::ParseError {
+        message: "Unexpected end of input.".to_string(),
+        summary: "unexpected end of input".to_string(),
+        span: Span { start: end, end },
+      })
     }
   }
 }
 
+/// Describes the innermost error of `e` in a single line, e.g. `expected ':' in Declaration`.
+fn summarize_error(e: &VerboseError<&str>) -> String {
+  let expected = e.errors.iter().find_map(|(_, kind)| match kind {
+    VerboseErrorKind::Char(c) => Some(format!("expected '{}'", c.escape_default())),
+    _ => None,
+  });
+  let context = e.errors.iter().find_map(|(_, kind)| match kind {
+    VerboseErrorKind::Context(context) => Some(*context),
+    _ => None,
+  });
+
+  match (expected, context) {
+    (Some(expected), Some(context)) => format!("{} in {}", expected, context),
+    (Some(expected), None) => expected,
+    (None, Some(context)) => format!("unexpected input in {}", context),
+    (None, None) => "unexpected input".to_string(),
+  }
+}
+
 pub fn parse_document_from_file(filepath: impl AsRef<Path>) -> Result<Document, Error> {
   let mut f = File::open(filepath)?;
   let mut buffer = vec![];
@@ -92,27 +129,213 @@ pub fn parse_document_from_file(filepath: impl AsRef<Path>) -> Result<Document,
   parse_document(contents.to_string())
 }
 
+/// While parsing, spans are measured from the end of the input, since the parsers only see the
+/// remaining input. Converts them into offsets from the start of the input.
+fn locate_spans(mut items: Vec<DocumentItem>, input_len: usize) -> Vec<DocumentItem> {
+  let locate = |span: &mut Span| {
+    *span = Span {
+      start: input_len - span.start,
+      end: input_len - span.end,
+    }
+  };
+
+  fn locate_ruleset_items(items: &mut [RuleSetItem], locate: &impl Fn(&mut Span)) {
+    for item in items {
+      match item {
+        RuleSetItem::Variable(v) => locate(&mut v.span),
+        RuleSetItem::Declaration(d) => locate(&mut d.span),
+        RuleSetItem::Extend(e) => locate(&mut e.span),
+        RuleSetItem::RuleSet(r) => locate_ruleset_items(&mut r.items, locate),
+      }
+    }
+  }
+
+  for item in items.iter_mut() {
+    match item {
+      DocumentItem::Variable(v) => locate(&mut v.span),
+      DocumentItem::Declaration(d) => locate(&mut d.span),
+      DocumentItem::RuleSet(r) => locate_ruleset_items(&mut r.items, &locate),
+      DocumentItem::Contrast(c) => locate(&mut c.span),
+    }
+  }
+  items
+}
+
+/// Applies `parser` and returns its output along with the span it consumed, measured from the end
+/// of the input. See `locate_spans`.
+fn spanned<'a, O, E, F>(mut parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, (O, Span), E>
+where
+  F: FnMut(&'a str) -> IResult<&'a str, O, E>,
+{
+  move |input: &'a str| {
+    let (remaining, output) = parser(input)?;
+    let span = Span {
+      start: input.len(),
+      end: remaining.len(),
+    };
+    Ok((remaining, (output, span)))
+  }
+}
+
+/// Expands dotted identifiers into nested rulesets, so that `Button.Primary: #ffffff` is
+/// equivalent to `Button { Primary: #ffffff }`. Rulesets with the same identifier on the same level
+/// are merged into the first one.
+fn expand_dotted_identifiers(items: Vec<DocumentItem>) -> Vec<DocumentItem> {
+  let mut expanded: Vec<DocumentItem> = vec![];
+
+  for item in items {
+    let item = match item {
+      DocumentItem::Variable(_) | DocumentItem::Contrast(_) => {
+        expanded.push(item);
+        continue;
+      }
+      DocumentItem::Declaration(d) => expand_ruleset_item(RuleSetItem::Declaration(d)),
+      DocumentItem::RuleSet(r) => expand_ruleset_item(RuleSetItem::RuleSet(r)),
+    };
+
+    match item {
+      RuleSetItem::Variable(v) => expanded.push(DocumentItem::Variable(v)),
+      RuleSetItem::Declaration(d) => expanded.push(DocumentItem::Declaration(d)),
+      RuleSetItem::Extend(_) => unreachable!("@extend is only parsed inside rulesets"),
+      RuleSetItem::RuleSet(ruleset) => {
+        let existing = expanded.iter_mut().find_map(|existing| match existing {
+          DocumentItem::RuleSet(existing) if existing.identifier == ruleset.identifier => {
+            Some(existing)
+          }
+          _ => None,
+        });
+
+        match existing {
+          Some(existing) => merge_ruleset(existing, ruleset),
+          None => expanded.push(DocumentItem::RuleSet(ruleset)),
+        }
+      }
+    }
+  }
+
+  expanded
+}
+
+fn expand_ruleset_item(item: RuleSetItem) -> RuleSetItem {
+  let identifier = match &item {
+    RuleSetItem::Variable(d) | RuleSetItem::Declaration(d) => &d.identifier,
+    RuleSetItem::Extend(_) => return item,
+    RuleSetItem::RuleSet(r) => &r.identifier,
+  };
+
+  let (parent, leaf) = match identifier.rsplit_once('.') {
+    Some((parent, leaf)) => (Some(parent.to_string()), leaf.to_string()),
+    None => (None, identifier.to_string()),
+  };
+
+  let item = match item {
+    item @ RuleSetItem::Extend(_) => item,
+    RuleSetItem::Variable(v) => RuleSetItem::Variable(Declaration {
+      identifier: leaf,
+      ..v
+    }),
+    RuleSetItem::Declaration(d) => RuleSetItem::Declaration(Declaration {
+      identifier: leaf,
+      ..d
+    }),
+    RuleSetItem::RuleSet(r) => {
+      let mut items = vec![];
+      merge_ruleset_items(&mut items, r.items);
+      RuleSetItem::RuleSet(RuleSet {
+        identifier: leaf,
+        items,
+        doc: r.doc,
+      })
+    }
+  };
+
+  match parent {
+    Some(parent) => expand_ruleset_item(RuleSetItem::RuleSet(RuleSet {
+      identifier: parent,
+      items: vec![item],
+      doc: vec![],
+    })),
+    None => item,
+  }
+}
+
+fn merge_ruleset_items(items: &mut Vec<RuleSetItem>, additional_items: Vec<RuleSetItem>) {
+  for item in additional_items {
+    match expand_ruleset_item(item) {
+      RuleSetItem::RuleSet(ruleset) => {
+        let existing = items.iter_mut().find_map(|existing| match existing {
+          RuleSetItem::RuleSet(existing) if existing.identifier == ruleset.identifier => {
+            Some(existing)
+          }
+          _ => None,
+        });
+
+        match existing {
+          Some(existing) => merge_ruleset(existing, ruleset),
+          None => items.push(RuleSetItem::RuleSet(ruleset)),
+        }
+      }
+      item => items.push(item),
+    }
+  }
+}
+
+/// Merges `other` into `ruleset`. The doc comments of `ruleset` take precedence.
+fn merge_ruleset(ruleset: &mut RuleSet, other: RuleSet) {
+  if ruleset.doc.is_empty() {
+    ruleset.doc = other.doc;
+  }
+  merge_ruleset_items(&mut ruleset.items, other.items);
+}
+
 fn single_line_comment<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
   input: &'a str,
-) -> IResult<&'a str, &str, E> {
+) -> IResult<&'a str, &'a str, E> {
   context("Single Line Comment", preceded(tag("//"), not_line_ending))(input)
 }
 
+/// A doc comment starts with exactly three slashes. Returns its text without the leading space.
+fn doc_comment<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, &'a str, E> {
+  context(
+    "Doc Comment",
+    map(
+      preceded(pair(tag("///"), not(char('/'))), not_line_ending),
+      |text: &str| text.strip_prefix(' ').unwrap_or(text).trim_end(),
+    ),
+  )(input)
+}
+
+/// The comments on the lines preceding an item. Returns the lines of the doc comments, other
+/// comments are skipped.
+fn leading_comments<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, Vec<String>, E> {
+  map(
+    many0(terminated(
+      alt((
+        map(doc_comment, |text| Some(text.to_string())),
+        map(single_line_comment, |_| None),
+      )),
+      terminated(newline, multispace0),
+    )),
+    |lines| lines.into_iter().flatten().collect(),
+  )(input)
+}
+
 fn line_delimiter<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
   input: &'a str,
 ) -> IResult<&'a str, char, E> {
   cut(preceded(
     space0,
-    preceded(
-      opt(single_line_comment),
-      terminated(newline, multiline_whitespace),
-    ),
+    preceded(opt(single_line_comment), terminated(newline, multispace0)),
   ))(input)
 }
 
 fn multiline_whitespace<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
   input: &'a str,
-) -> IResult<&'a str, Vec<&str>, E> {
+) -> IResult<&'a str, Vec<&'a str>, E> {
   delimited(
     multispace0,
     separated_list0(terminated(newline, multispace0), single_line_comment),
@@ -131,11 +354,13 @@ fn ruleset<
 ) -> IResult<&'a str, RuleSet, E> {
   let body = |input| {
     delimited(
-      terminated(char('{'), multiline_whitespace),
+      terminated(char('{'), multispace0),
       cut(many0(terminated(
         alt((
+          map(extend, RuleSetItem::Extend),
+          map(variable, RuleSetItem::Variable),
           map(ruleset, RuleSetItem::RuleSet),
-          map(declaration(value), RuleSetItem::Declaration),
+          map(documented_declaration, RuleSetItem::Declaration),
         )),
         line_delimiter,
       ))),
@@ -145,10 +370,30 @@ fn ruleset<
 
   context(
     "RuleSet",
-    map(separated_pair(identifier, space0, body), |res| RuleSet {
-      identifier: res.0.to_string(),
-      items: res.1,
-    }),
+    map(
+      pair(leading_comments, separated_pair(identifier, space0, body)),
+      |(doc, (identifier, items))| RuleSet {
+        identifier,
+        items,
+        doc,
+      },
+    ),
+  )(input)
+}
+
+/// `@extend` followed by the path of the ruleset to inherit from, e.g. `@extend Button`.
+fn extend<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, Extend, E> {
+  context(
+    "Extend",
+    map(
+      preceded(
+        space0,
+        spanned(preceded(tag("@extend"), cut(preceded(space1, identifier)))),
+      ),
+      |(path, span)| Extend { path, span },
+    ),
   )(input)
 }
 
@@ -164,19 +409,141 @@ fn variable<
   context(
     "Variable",
     map(
-      separated_pair(
-        variable_identifier,
-        cut(terminated(preceded(space0, char(':')), space0)),
+      pair(
+        leading_comments,
+        preceded(
+          space0,
+          spanned(separated_pair(
+            variable_identifier,
+            cut(terminated(preceded(space0, char(':')), space0)),
+            alt((map(scale, Value::Scale), value)),
+          )),
+        ),
+      ),
+      |(doc, ((identifier, value), span))| Declaration {
+        identifier,
         value,
+        doc,
+        annotations: vec![],
+        span,
+      },
+    ),
+  )(input)
+}
+
+fn documented_declaration<
+  'a,
+  E: ParseError<&'a str>
+    + ContextError<&'a str>
+    + FromExternalError<&'a str, ParseIntError>
+    + FromExternalError<&'a str, Error>,
+>(
+  input: &'a str,
+) -> IResult<&'a str, Declaration<Value>, E> {
+  map(
+    tuple((
+      leading_comments,
+      many0(terminated(annotation, multispace1)),
+      declaration(value),
+    )),
+    |(doc, annotations, declaration)| Declaration {
+      doc,
+      annotations,
+      ..declaration
+    },
+  )(input)
+}
+
+/// Either `@deprecated`, optionally followed by a message in parentheses, or `@renamed(Identifier)`.
+fn annotation<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, Annotation, E> {
+  context(
+    "Annotation",
+    preceded(
+      char('@'),
+      cut(alt((
+        map(
+          preceded(tag("deprecated"), opt(arguments(string_literal))),
+          |message| Annotation::Deprecated {
+            message: message.map(|message| message.to_string()),
+          },
+        ),
+        map(
+          preceded(tag("renamed"), cut(arguments(identifier))),
+          |identifier| Annotation::Renamed { identifier },
+        ),
+      ))),
+    ),
+  )(input)
+}
+
+/// The argument of an annotation, enclosed in parentheses.
+fn arguments<'a, F, O, E: ParseError<&'a str> + ContextError<&'a str>>(
+  argument: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+  F: FnMut(&'a str) -> IResult<&'a str, O, E>,
+{
+  delimited(
+    terminated(char('('), space0),
+    cut(argument),
+    cut(preceded(space0, char(')'))),
+  )
+}
+
+/// `@contrast(Foreground on Background, Level)`, where level is one of `AA`, `AA-large`, `AAA`
+/// and `AAA-large`.
+fn contrast_requirement<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, ContrastRequirement, E> {
+  let level = alt((
+    map(tag("AAA-large"), |_| ContrastLevel::AAALarge),
+    map(tag("AAA"), |_| ContrastLevel::AAA),
+    map(tag("AA-large"), |_| ContrastLevel::AALarge),
+    map(tag("AA"), |_| ContrastLevel::AA),
+  ));
+
+  context(
+    "Contrast",
+    map(
+      preceded(
+        leading_comments,
+        spanned(preceded(
+          tag("@contrast"),
+          cut(arguments(tuple((
+            terminated(identifier, delimited(space1, tag("on"), space1)),
+            identifier,
+            preceded(
+              delimited(space0, char(','), space0),
+              context("Level", level),
+            ),
+          )))),
+        )),
       ),
-      |res| {
-        let (identifier, value) = res;
-        Declaration { identifier, value }
+      |((foreground, background, level), span)| ContrastRequirement {
+        foreground,
+        background,
+        level,
+        span,
       },
     ),
   )(input)
 }
 
+fn string_literal<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, &'a str, E> {
+  context(
+    "String",
+    delimited(
+      char('"'),
+      take_while(|c| c != '"' && c != '\n'),
+      cut(char('"')),
+    ),
+  )(input)
+}
+
 fn value<
   'a,
   E: ParseError<&'a str>
@@ -195,6 +562,74 @@ fn value<
   )(input)
 }
 
+/// `scale(#1E6FFF, steps: 9, from: 10%, to: 90%)`, where the named arguments are optional but must
+/// be given in this order. They default to 9 steps from 10% to 90%.
+fn scale<
+  'a,
+  E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, ParseIntError>,
+>(
+  input: &'a str,
+) -> IResult<&'a str, Scale, E> {
+  let steps = context(
+    "Steps of at least 1",
+    verify(map_res(digit1, |s: &str| s.parse::<u32>()), |steps| {
+      *steps >= 1
+    }),
+  );
+  let percentage = || {
+    context(
+      "Percentage between 0% - 100%",
+      verify(alpha_value, |val| *val <= 1.0),
+    )
+  };
+
+  context(
+    "Scale",
+    map(
+      preceded(
+        tag("scale"),
+        cut(delimited(
+          terminated(char('('), space0),
+          tuple((
+            context(
+              "Color",
+              alt((
+                hex_color,
+                rgba_color,
+                map(color_function, |(color, _)| color),
+              )),
+            ),
+            opt(scale_argument("steps", steps)),
+            opt(scale_argument("from", percentage())),
+            opt(scale_argument("to", percentage())),
+          )),
+          preceded(space0, char(')')),
+        )),
+      ),
+      |(base, steps, from, to)| Scale {
+        base,
+        steps: steps.unwrap_or(9),
+        from: from.unwrap_or(0.1),
+        to: to.unwrap_or(0.9),
+      },
+    ),
+  )(input)
+}
+
+/// A named argument of a scale preceded by a comma, e.g. `, steps: 9`.
+fn scale_argument<'a, F, O, E: ParseError<&'a str> + ContextError<&'a str>>(
+  name: &'static str,
+  argument: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+  F: FnMut(&'a str) -> IResult<&'a str, O, E>,
+{
+  preceded(
+    tuple((space0, char(','), space0, tag(name), space0)),
+    cut(preceded(terminated(char(':'), space0), argument)),
+  )
+}
+
 fn colorset_value<
   'a,
   E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, ParseIntError>,
@@ -206,6 +641,8 @@ fn colorset_value<
     alt((
       map(hex_color, ColorSetValue::Color),
       map(rgba_color, ColorSetValue::Color),
+      map(color_function, |(color, _)| ColorSetValue::Color(color)),
+      map(reference_value, ColorSetValue::Reference),
       map(variable_value, ColorSetValue::Variable),
     )),
   )(input)
@@ -246,12 +683,106 @@ fn rgba_color<
       ),
       |res| {
         let (r, g, b, a) = res;
-        Color { r, g, b, a }
+        Color {
+          r,
+          g,
+          b,
+          a,
+          linear: None,
+        }
       },
     ),
   )(input)
 }
 
+/// A color in a perceptual color space, e.g. `oklch(70% 0.1 250 / 50%)`, converted into sRGB.
+/// Returns the color along with whether it was outside of the sRGB gamut and had to be mapped into
+/// it. See `color_space` for how colors are mapped.
+pub(crate) fn color_function<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, (Color, bool), E> {
+  context(
+    "Color Function",
+    alt((
+      map(
+        preceded(
+          tag("oklch"),
+          cut(color_arguments(number(1.0), number(0.4), hue)),
+        ),
+        |([l, c, h], alpha)| Oklch { l, c, h }.to_color(alpha),
+      ),
+      map(
+        preceded(
+          tag("oklab"),
+          cut(color_arguments(number(1.0), number(0.4), number(0.4))),
+        ),
+        |([l, a, b], alpha)| Oklab { l, a, b }.to_color(alpha),
+      ),
+      map(
+        preceded(
+          tag("lch"),
+          cut(color_arguments(number(100.0), number(150.0), hue)),
+        ),
+        |([l, c, h], alpha)| Lch { l, c, h }.to_color(alpha),
+      ),
+    )),
+  )(input)
+}
+
+/// The three space-separated components of a color function, optionally followed by a slash and
+/// an alpha value, enclosed in parentheses.
+fn color_arguments<'a, F, G, H, E: ParseError<&'a str> + ContextError<&'a str>>(
+  first: F,
+  second: G,
+  third: H,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ([f64; 3], f32), E>
+where
+  F: FnMut(&'a str) -> IResult<&'a str, f64, E>,
+  G: FnMut(&'a str) -> IResult<&'a str, f64, E>,
+  H: FnMut(&'a str) -> IResult<&'a str, f64, E>,
+{
+  let alpha = context(
+    "Alpha Value",
+    verify(number(1.0), |alpha| (0.0..=1.0).contains(alpha)),
+  );
+
+  map(
+    delimited(
+      terminated(char('('), space0),
+      tuple((
+        first,
+        preceded(space1, second),
+        preceded(space1, third),
+        opt(preceded(delimited(space0, char('/'), space0), alpha)),
+      )),
+      preceded(space0, char(')')),
+    ),
+    |(first, second, third, alpha)| ([first, second, third], alpha.unwrap_or(1.0) as f32),
+  )
+}
+
+/// A number or a percentage of `reference`, e.g. `50%` for half of the reference.
+fn number<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  reference: f64,
+) -> impl FnMut(&'a str) -> IResult<&'a str, f64, E> {
+  context(
+    "Number or Percentage",
+    alt((
+      map(terminated(double, char('%')), move |val| {
+        val * reference / 100.0
+      }),
+      double,
+    )),
+  )
+}
+
+/// A hue angle in degrees, optionally followed by `deg`.
+fn hue<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, f64, E> {
+  context("Hue", terminated(double, opt(tag("deg"))))(input)
+}
+
 fn hex_color<
   'a,
   E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, ParseIntError>,
@@ -270,7 +801,13 @@ fn hex_color<
       let g = ((rgb >> 8) & 0xff) as u8;
       let b = (rgb & 0xff) as u8;
       let a = alpha.unwrap_or(1.0);
-      Color { r, g, b, a }
+      Color {
+        r,
+        g,
+        b,
+        a,
+        linear: None,
+      }
     },
   )(input)
 }
@@ -301,24 +838,41 @@ pub fn colorset<
   )(input)
 }
 
+/// Identifiers consist of one or more segments separated by dots, where a dotted identifier
+/// (e.g. `Button.Primary`) is shorthand for nested rulesets. See `expand_dotted_identifiers`.
 pub fn identifier<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
   input: &'a str,
 ) -> IResult<&'a str, String, E> {
   context(
     "Identifier",
-    map(preceded(space0, alphanumeric1), |ident: &str| {
-      ident.to_string()
-    }),
+    map(
+      preceded(
+        space0,
+        recognize(separated_list1(char('.'), identifier_segment)),
+      ),
+      |ident: &str| ident.to_string(),
+    ),
   )(input)
 }
 
+/// A segment of an identifier starts with a letter, digit or underscore, followed by any number of
+/// letters, digits, underscores or hyphens (e.g. `brand-blue` or `grey_10`).
+fn identifier_segment<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+  input: &'a str,
+) -> IResult<&'a str, &'a str, E> {
+  recognize(pair(
+    satisfy(|c| c.is_ascii_alphanumeric() || c == '_'),
+    take_while(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+  ))(input)
+}
+
 pub fn variable_identifier<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
   input: &'a str,
 ) -> IResult<&'a str, String, E> {
   context(
     "Variable Identifier",
     map(
-      preceded(space0, preceded(char('$'), alphanumeric1)),
+      preceded(space0, preceded(char('$'), identifier_segment)),
       |ident: &str| ident.to_string(),
     ),
   )(input)
@@ -342,6 +896,27 @@ pub fn variable_value<
   )(input)
 }
 
+pub fn reference_value<
+  'a,
+  E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, ParseIntError>,
+>(
+  input: &'a str,
+) -> IResult<&'a str, Reference, E> {
+  context(
+    "Reference",
+    map(
+      tuple((
+        preceded(space0, preceded(char('&'), cut(identifier))),
+        opt(preceded(space1, alpha_value)),
+      )),
+      |(path, opacity)| Reference {
+        path,
+        opacity: opacity.unwrap_or(1.0),
+      },
+    ),
+  )(input)
+}
+
 pub fn declaration<'a, F, V, E: ParseError<&'a str> + ContextError<&'a str>>(
   value: F,
 ) -> impl Fn(&'a str) -> IResult<&'a str, Declaration<V>, E>
@@ -352,10 +927,20 @@ where
     context(
       "Declaration",
       map(
-        separated_pair(identifier, cut(preceded(space0, char(':'))), &value),
-        |res| {
-          let (identifier, value) = res;
-          Declaration { identifier, value }
+        preceded(
+          space0,
+          spanned(separated_pair(
+            identifier,
+            cut(preceded(space0, char(':'))),
+            &value,
+          )),
+        ),
+        |((identifier, value), span)| Declaration {
+          identifier,
+          value,
+          doc: vec![],
+          annotations: vec![],
+          span,
         },
       ),
     )(input)
@@ -382,12 +967,12 @@ fn alpha_value<
 ) -> IResult<&'a str, f32, E> {
   context(
     "Alpha Value",
-    map_res(terminated(digit1, tag("%")), |input| {
-      u32::from_str_radix(input, 10).map(|val| (val as f32) / 100.0)
+    map_res(terminated(digit1, tag("%")), |input: &str| {
+      input.parse::<u32>().map(|val| (val as f32) / 100.0)
     }),
   )(input)
 }
 
 fn is_hex_digit(c: char) -> bool {
-  c.is_digit(16)
+  c.is_ascii_hexdigit()
 }
@@ -0,0 +1,259 @@
+//! A stylesheet with all variables resolved into colors. This is what generators consume, so that
+//! resolution and naming are implemented once.
+
+use super::ast::{
+  self, Annotation, Color, ColorSetValue, DocumentItem as ASTDocumentItem,
+  RuleSetItem as ASTRuleSetItem, Span, Value as ASTValue,
+};
+use super::extend::expand_extends;
+use super::scale::expand_scales;
+use super::{AssetNaming, Error, ResolvedVariable, VarContext};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Document {
+  /// The top-level rulesets and declarations in the order of the stylesheet.
+  pub items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
+pub enum Item {
+  RuleSet(RuleSet),
+  Declaration(Declaration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleSet {
+  /// The identifiers of the enclosing rulesets and the ruleset itself, e.g. `["Text", "Link"]`.
+  pub path: Vec<String>,
+  pub items: Vec<Item>,
+  /// The lines of the doc comments (`///`) preceding the ruleset.
+  pub doc: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Declaration {
+  /// The identifiers of the enclosing rulesets and the declaration itself, e.g.
+  /// `["Text", "Primary"]`.
+  pub path: Vec<String>,
+  pub value: Value,
+  /// The variable the value has been taken from, e.g. `brand` for `Primary: $brand`.
+  pub variable: Option<String>,
+  /// The lines of the doc comments (`///`) preceding the declaration.
+  pub doc: Vec<String>,
+  pub annotations: Vec<Annotation>,
+  pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
+pub enum Value {
+  Color(Color),
+  ColorSet(ColorSet),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorSet {
+  pub light: SourcedColor,
+  pub dark: SourcedColor,
+}
+
+/// A resolved color and the variable it has been taken from, if any.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourcedColor {
+  pub color: Color,
+  pub variable: Option<String>,
+}
+
+impl Document {
+  /// Expands `@extend` and resolves the variables and references of the declarations of `doc`.
+  /// Fails if a variable, declaration or extended ruleset does not exist, on circular references
+  /// or if a colorset is used as the light or dark color of another colorset.
+  pub fn derive_from(doc: &ast::Document) -> Result<Self, Error> {
+    let doc = &expand_extends(&expand_scales(doc))?;
+    let ctx = VarContext::derive_from(doc);
+    let mut items = vec![];
+
+    for item in &doc.items {
+      match item {
+        ASTDocumentItem::RuleSet(ruleset) => {
+          items.push(Item::RuleSet(RuleSet::derive_from(ruleset, &[], &ctx)?))
+        }
+        ASTDocumentItem::Declaration(decl) => items.push(Item::Declaration(
+          Declaration::derive_from(decl, &[], &ctx)?,
+        )),
+        ASTDocumentItem::Variable(_) | ASTDocumentItem::Contrast(_) => {}
+      }
+    }
+
+    Ok(Document { items })
+  }
+
+  /// Returns all declarations, depth-first in the order of the stylesheet.
+  pub fn declarations(&self) -> Vec<&Declaration> {
+    collect_declarations(&self.items)
+  }
+
+  /// Returns the declaration at the dot-separated `path`, e.g. `Text.Primary`.
+  pub fn find_declaration(&self, path: &str) -> Option<&Declaration> {
+    self
+      .declarations()
+      .into_iter()
+      .find(|decl| decl.dotted_path() == path)
+  }
+}
+
+impl RuleSet {
+  fn derive_from<'a>(
+    ruleset: &'a ast::RuleSet,
+    parent: &[String],
+    ctx: &VarContext<'a>,
+  ) -> Result<Self, Error> {
+    let path = appending(parent, &ruleset.identifier);
+    let ctx = ctx.scoped(ruleset);
+    let mut items = vec![];
+
+    for item in &ruleset.items {
+      match item {
+        ASTRuleSetItem::Variable(_) | ASTRuleSetItem::Extend(_) => {}
+        ASTRuleSetItem::RuleSet(ruleset) => {
+          items.push(Item::RuleSet(RuleSet::derive_from(ruleset, &path, &ctx)?))
+        }
+        ASTRuleSetItem::Declaration(decl) => items.push(Item::Declaration(
+          Declaration::derive_from(decl, &path, &ctx)?,
+        )),
+      }
+    }
+
+    Ok(RuleSet {
+      path,
+      items,
+      doc: ruleset.doc.clone(),
+    })
+  }
+
+  pub fn identifier(&self) -> &str {
+    self.path.last().map_or("", String::as_str)
+  }
+
+  /// Returns all declarations, depth-first in the order of the stylesheet.
+  pub fn declarations(&self) -> Vec<&Declaration> {
+    collect_declarations(&self.items)
+  }
+}
+
+impl Declaration {
+  fn derive_from(
+    decl: &ast::Declaration<ASTValue>,
+    parent: &[String],
+    ctx: &VarContext,
+  ) -> Result<Self, Error> {
+    let sourced = |value: &ColorSetValue, color: Color| SourcedColor {
+      color,
+      variable: match value {
+        ColorSetValue::Variable(variable) => Some(variable.identifier.clone()),
+        ColorSetValue::Reference(_) | ColorSetValue::Color(_) => None,
+      },
+    };
+    let unsourced = |resolved: ResolvedVariable| match resolved {
+      ResolvedVariable::Color(color) => Value::Color(color),
+      ResolvedVariable::ColorSet(colorset) => Value::ColorSet(ColorSet {
+        light: SourcedColor {
+          color: colorset.light,
+          variable: None,
+        },
+        dark: SourcedColor {
+          color: colorset.dark,
+          variable: None,
+        },
+      }),
+    };
+
+    let locate = |e: Error| e.located_at(decl.span);
+    let (value, variable) = match &decl.value {
+      ASTValue::Color(color) => (Value::Color(color.clone()), None),
+      ASTValue::ColorSet(colorset) => {
+        let resolved = ctx.resolve_colorset(colorset).map_err(locate)?;
+        let value = Value::ColorSet(ColorSet {
+          light: sourced(&colorset.light, resolved.light),
+          dark: sourced(&colorset.dark, resolved.dark),
+        });
+        (value, None)
+      }
+      ASTValue::Variable(variable) => (
+        unsourced(ctx.resolve(variable).map_err(locate)?),
+        Some(variable.identifier.clone()),
+      ),
+      ASTValue::Reference(reference) => (
+        unsourced(ctx.resolve_reference(reference).map_err(locate)?),
+        None,
+      ),
+      ASTValue::Scale(_) => {
+        return Err(Error::ResolveError {
+          message: format!(
+            "{} is assigned a scale. Only variables can be assigned a scale.",
+            decl.identifier
+          ),
+          span: Some(decl.span),
+        });
+      }
+    };
+
+    Ok(Declaration {
+      path: appending(parent, &decl.identifier),
+      value,
+      variable,
+      doc: decl.doc.clone(),
+      annotations: decl.annotations.clone(),
+      span: decl.span,
+    })
+  }
+
+  pub fn identifier(&self) -> &str {
+    self.path.last().map_or("", String::as_str)
+  }
+
+  /// The path of the declaration in the stylesheet, e.g. `Text.Primary`.
+  pub fn dotted_path(&self) -> String {
+    self.path.join(".")
+  }
+
+  /// The name of the color in the asset catalog, e.g. `TextPrimary`.
+  pub fn asset_name(&self, naming: AssetNaming) -> String {
+    self.path.iter().fold(String::new(), |name, identifier| {
+      naming.join(&name, identifier)
+    })
+  }
+
+  /// The light and dark color. Both are equal for single colors.
+  pub fn appearances(&self) -> (&Color, &Color) {
+    match &self.value {
+      Value::Color(color) => (color, color),
+      Value::ColorSet(colorset) => (&colorset.light.color, &colorset.dark.color),
+    }
+  }
+}
+
+fn appending(path: &[String], identifier: &str) -> Vec<String> {
+  let mut path = path.to_vec();
+  path.push(identifier.to_string());
+  path
+}
+
+fn collect_declarations(items: &[Item]) -> Vec<&Declaration> {
+  let mut declarations = vec![];
+  for item in items {
+    match item {
+      Item::Declaration(decl) => declarations.push(decl),
+      Item::RuleSet(ruleset) => declarations.extend(ruleset.declarations()),
+    }
+  }
+  declarations
+}
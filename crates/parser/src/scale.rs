@@ -0,0 +1,104 @@
+//! Replaces variables assigned a `scale(…)` with one variable per step, so that the steps are
+//! resolved like any other variable.
+
+use super::ast::{Color, Declaration, Document, DocumentItem, RuleSet, RuleSetItem, Scale, Value};
+use super::color_space::Oklch;
+
+/// Returns `doc` with each variable assigned a scale replaced by the variables of its steps, e.g.
+/// `$blue100` to `$blue900` for a scale of nine steps assigned to `$blue`.
+pub fn expand_scales(doc: &Document) -> Document {
+  let items = doc
+    .items
+    .iter()
+    .flat_map(|item| match item {
+      DocumentItem::Variable(variable) => expand(variable)
+        .into_iter()
+        .map(DocumentItem::Variable)
+        .collect(),
+      DocumentItem::RuleSet(ruleset) => vec![DocumentItem::RuleSet(expand_ruleset(ruleset))],
+      item => vec![item.clone()],
+    })
+    .collect();
+
+  Document { items }
+}
+
+fn expand_ruleset(ruleset: &RuleSet) -> RuleSet {
+  let items = ruleset
+    .items
+    .iter()
+    .flat_map(|item| match item {
+      RuleSetItem::Variable(variable) => expand(variable)
+        .into_iter()
+        .map(RuleSetItem::Variable)
+        .collect(),
+      RuleSetItem::RuleSet(ruleset) => vec![RuleSetItem::RuleSet(expand_ruleset(ruleset))],
+      item => vec![item.clone()],
+    })
+    .collect();
+
+  RuleSet {
+    identifier: ruleset.identifier.clone(),
+    items,
+    doc: ruleset.doc.clone(),
+  }
+}
+
+/// Returns the variables of the steps if `variable` is assigned a scale, or the variable itself.
+/// The steps share the doc comments and span of the scale.
+fn expand(variable: &Declaration<Value>) -> Vec<Declaration<Value>> {
+  let scale = match &variable.value {
+    Value::Scale(scale) => scale,
+    _ => return vec![variable.clone()],
+  };
+
+  scale
+    .colors()
+    .into_iter()
+    .enumerate()
+    .map(|(idx, color)| Declaration {
+      identifier: format!("{}{}", variable.identifier, (idx + 1) * 100),
+      value: Value::Color(color),
+      doc: variable.doc.clone(),
+      annotations: variable.annotations.clone(),
+      span: variable.span,
+    })
+    .collect()
+}
+
+impl Scale {
+  /// Returns the colors of the steps from light to dark. Each step keeps the chroma and hue of the
+  /// base color in OKLCH, with the lightness evenly distributed between `from` and `to`. Steps
+  /// outside of the sRGB gamut are mapped into it by reducing their chroma.
+  pub fn colors(&self) -> Vec<Color> {
+    self.steps().into_iter().map(|(color, _)| color).collect()
+  }
+
+  /// Returns the colors of the steps like [`Scale::colors`], along with whether they have been
+  /// mapped into the sRGB gamut.
+  pub fn steps(&self) -> Vec<(Color, bool)> {
+    let base = Oklch::from_color(&self.base);
+
+    (0..self.steps)
+      .map(|step| {
+        let darkness = if self.steps > 1 {
+          self.from + (self.to - self.from) * step as f32 / (self.steps - 1) as f32
+        } else {
+          self.from
+        };
+        let darkness = f64::from(darkness);
+        let (color, mapped) = Oklch {
+          l: 1.0 - darkness,
+          ..base
+        }
+        .to_color(self.base.a);
+        // Steps stay in sRGB like their base color, even when converted into a wider gamut.
+        let color = Color {
+          linear: None,
+          ..color
+        };
+        (color, mapped)
+      })
+      .collect()
+  }
+}
@@ -1,9 +1,19 @@
-use super::ast::{Color, ColorSet, ColorSetValue, Document, DocumentItem, Value, Variable};
+use super::ast::{
+  Color, ColorSet, ColorSetValue, Declaration, Document, DocumentItem, Reference, RuleSet,
+  RuleSetItem, Value, Variable,
+};
 use super::error::Error;
 use std::collections::HashMap;
+use std::ptr;
+use std::rc::Rc;
 
+/// The variables and declarations visible at a point of the stylesheet. Variables declared in a
+/// ruleset are only visible inside that ruleset and its nested rulesets, and shadow variables of
+/// the same name declared in an enclosing scope.
+#[derive(Clone)]
 pub struct VarContext<'a> {
-  map: HashMap<String, &'a Value>,
+  /// The document followed by each enclosing ruleset, outermost first.
+  scopes: Vec<Rc<Scope<'a>>>,
 }
 
 pub enum ResolvedVariable {
@@ -16,70 +26,429 @@ pub struct ResolvedColorSet {
   pub dark: Color,
 }
 
+struct Scope<'a> {
+  /// The identifiers of the rulesets leading to the scope, empty for the document.
+  path: Vec<&'a str>,
+  items: Items<'a>,
+  variables: HashMap<&'a str, &'a Declaration<Value>>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Items<'a> {
+  Document(&'a [DocumentItem]),
+  RuleSet(&'a [RuleSetItem]),
+}
+
+pub(crate) enum Target<'a> {
+  Declaration(&'a Declaration<Value>),
+  RuleSet(&'a RuleSet),
+}
+
+/// The variables and declarations currently being resolved along with their names, e.g. `$ink` or
+/// `Text.Primary`, to detect circular references.
+type Stack<'a> = Vec<(String, &'a Declaration<Value>)>;
+
 impl<'a> VarContext<'a> {
   pub fn resolve(&self, variable: &Variable) -> Result<ResolvedVariable, Error> {
-    let value = match self.map.get(&variable.identifier) {
-      Some(value) => value,
+    self.resolve_variable(variable, self.scopes.len(), &mut vec![])
+  }
+
+  /// Resolves the declaration `reference` refers to and applies the opacity of the reference.
+  pub fn resolve_reference(&self, reference: &Reference) -> Result<ResolvedVariable, Error> {
+    self.resolve_reference_within(reference, self.scopes.len(), &mut vec![])
+  }
+
+  pub fn resolve_colorset(&self, colorset: &ColorSet) -> Result<ResolvedColorSet, Error> {
+    self.resolve_colorset_within(colorset, None, &mut vec![])
+  }
+
+  /// Returns the declaration of the variable `reference` refers to. `referrer` is the variable
+  /// whose value contains the reference, if any, which must be declared in the innermost scope.
+  pub fn find(
+    &self,
+    reference: &Variable,
+    referrer: Option<&Declaration<Value>>,
+  ) -> Option<&'a Declaration<Value>> {
+    let depth = match referrer {
+      Some(referrer) => variable_depth(reference, &referrer.identifier, self.scopes.len() - 1),
+      None => self.scopes.len(),
+    };
+    self
+      .lookup_variable(&reference.identifier, depth)
+      .map(|(variable, _)| variable)
+  }
+
+  /// Returns the variables visible in the innermost scope in the order of the stylesheet.
+  pub fn visible_variables(&self) -> Vec<&'a Declaration<Value>> {
+    let mut variables: Vec<&Declaration<Value>> = vec![];
+    for scope in self.scopes.iter().rev() {
+      for variable in scope.variables.values() {
+        if !variables
+          .iter()
+          .any(|visible| visible.identifier == variable.identifier)
+        {
+          variables.push(variable);
+        }
+      }
+    }
+    variables.sort_by_key(|variable| variable.span.start);
+    variables
+  }
+
+  /// Returns the declaration of `variable` followed by the variables it is an alias of, e.g. `$ink`
+  /// and `$brand` for `$ink: $brand`. Colorsets are followed through their dark color if `dark` is
+  /// set, through their light color otherwise.
+  pub fn aliases(&self, variable: &Variable, dark: bool) -> Vec<&'a Declaration<Value>> {
+    let mut aliases: Vec<&Declaration<Value>> = vec![];
+    let mut next = variable;
+    let mut depth = self.scopes.len();
+
+    while let Some((declaration, idx)) = self.lookup_variable(&next.identifier, depth) {
+      if aliases.iter().any(|alias| ptr::eq(*alias, declaration)) {
+        break;
+      }
+      aliases.push(declaration);
+
+      next = match &declaration.value {
+        Value::Variable(variable) => variable,
+        Value::ColorSet(colorset) => {
+          let value = if dark {
+            &colorset.dark
+          } else {
+            &colorset.light
+          };
+          match value {
+            ColorSetValue::Variable(variable) => variable,
+            _ => break,
+          }
+        }
+        _ => break,
+      };
+      depth = variable_depth(next, &declaration.identifier, idx);
+    }
+
+    aliases
+  }
+
+  /// Looks up the variable named `identifier` in the outermost `depth` scopes. Returns the
+  /// variable along with the index of the scope declaring it.
+  fn lookup_variable(
+    &self,
+    identifier: &str,
+    depth: usize,
+  ) -> Option<(&'a Declaration<Value>, usize)> {
+    self.scopes[..depth]
+      .iter()
+      .enumerate()
+      .rev()
+      .find_map(|(idx, scope)| {
+        scope
+          .variables
+          .get(identifier)
+          .map(|variable| (*variable, idx))
+      })
+  }
+
+  /// Looks up the dotted `path` relative to each of the outermost `depth` scopes, innermost first.
+  /// Returns the context of the item the path refers to along with the item.
+  fn lookup_path(&self, path: &str, depth: usize) -> Option<(VarContext<'a>, Target<'a>)> {
+    self.scopes[..depth]
+      .iter()
+      .enumerate()
+      .rev()
+      .find_map(|(idx, scope)| {
+        let mut ctx = VarContext {
+          scopes: self.scopes[..=idx].to_vec(),
+        };
+        let mut segments = path.split('.');
+        let mut target = scope.items.child(segments.next()?)?;
+
+        for segment in segments {
+          match target {
+            Target::RuleSet(ruleset) => {
+              ctx = ctx.scoped(ruleset);
+              target = Items::RuleSet(&ruleset.items).child(segment)?;
+            }
+            Target::Declaration(_) => return None,
+          }
+        }
+        Some((ctx, target))
+      })
+  }
+
+  fn resolve_variable(
+    &self,
+    variable: &Variable,
+    depth: usize,
+    stack: &mut Stack<'a>,
+  ) -> Result<ResolvedVariable, Error> {
+    let (declaration, idx) = match self.lookup_variable(&variable.identifier, depth) {
+      Some(found) => found,
       None => {
         return Err(Error::ResolveError {
           message: format!(
             "Could not find variable with identifier {}.",
             variable.identifier
           ),
+          span: None,
         });
       }
     };
-    match value {
-      Value::Variable(identifier) => self.resolve(identifier),
+
+    let name = format!("${}", declaration.identifier);
+    with_entry(stack, name, declaration, |stack| match &declaration.value {
       Value::Color(color) => Ok(ResolvedVariable::Color(variable.resolve_against(color))),
+      value => self.resolve_value(value, Some((&declaration.identifier, idx)), stack),
+    })
+  }
+
+  fn resolve_reference_within(
+    &self,
+    reference: &Reference,
+    depth: usize,
+    stack: &mut Stack<'a>,
+  ) -> Result<ResolvedVariable, Error> {
+    let (ctx, declaration) = match self.lookup_path(&reference.path, depth) {
+      Some((ctx, Target::Declaration(declaration))) => (ctx, declaration),
+      Some((_, Target::RuleSet(_))) => {
+        return Err(Error::ResolveError {
+          message: format!(
+            "&{} refers to a ruleset. References must refer to a declaration.",
+            reference.path
+          ),
+          span: None,
+        });
+      }
+      None => {
+        return Err(Error::ResolveError {
+          message: format!("Could not find declaration with path {}.", reference.path),
+          span: None,
+        });
+      }
+    };
+
+    let mut path = ctx.path();
+    path.push(&declaration.identifier);
+    let resolved = with_entry(stack, path.join("."), declaration, |stack| {
+      ctx.resolve_value(&declaration.value, None, stack)
+    })?;
+
+    Ok(match resolved {
+      ResolvedVariable::Color(color) => ResolvedVariable::Color(with_opacity(color, reference)),
+      ResolvedVariable::ColorSet(colorset) => ResolvedVariable::ColorSet(ResolvedColorSet {
+        light: with_opacity(colorset.light, reference),
+        dark: with_opacity(colorset.dark, reference),
+      }),
+    })
+  }
+
+  /// Resolves `value`, which is either the value of a declaration in the innermost scope or of
+  /// the variable `referrer` declared in the scope at the given index.
+  fn resolve_value(
+    &self,
+    value: &Value,
+    referrer: Option<(&str, usize)>,
+    stack: &mut Stack<'a>,
+  ) -> Result<ResolvedVariable, Error> {
+    match value {
+      Value::Color(color) => Ok(ResolvedVariable::Color(color.clone())),
+      Value::Variable(variable) => {
+        self.resolve_variable(variable, self.variable_depth(variable, referrer), stack)
+      }
+      Value::Reference(reference) => {
+        self.resolve_reference_within(reference, self.reference_depth(referrer), stack)
+      }
       Value::ColorSet(colorset) => self
-        .resolve_colorset(colorset)
+        .resolve_colorset_within(colorset, referrer, stack)
         .map(ResolvedVariable::ColorSet),
+      Value::Scale(_) => Err(Error::ResolveError {
+        message: match referrer {
+          Some((referrer, _)) => format!(
+            "The scale ${} must be expanded before it is resolved.",
+            referrer
+          ),
+          None => "Only variables can be assigned a scale.".to_string(),
+        },
+        span: None,
+      }),
     }
   }
 
-  pub fn resolve_colorset(&self, colorset: &ColorSet) -> Result<ResolvedColorSet, Error> {
-    let light: Color = match &colorset.light {
-      ColorSetValue::Color(color) => Ok(color.clone()),
-      ColorSetValue::Variable(ref light_variable) => match self.resolve(light_variable)? {
-        ResolvedVariable::Color(color) => Ok(color),
-        ResolvedVariable::ColorSet(_) => Err(Error::ResolveError {
-          message: format!(
-            "Attempt to assign a colorset to the light property of another colorset via variable {}.",
-            light_variable.identifier
-          )
-        }),
-      },
-    }?;
+  /// Resolves `colorset`. See `resolve_value` for `referrer`.
+  fn resolve_colorset_within(
+    &self,
+    colorset: &ColorSet,
+    referrer: Option<(&str, usize)>,
+    stack: &mut Stack<'a>,
+  ) -> Result<ResolvedColorSet, Error> {
+    let mut resolve = |value: &ColorSetValue, property: &str| {
+      let (resolved, source) = match value {
+        ColorSetValue::Color(color) => return Ok(color.clone()),
+        ColorSetValue::Variable(variable) => (
+          self.resolve_variable(variable, self.variable_depth(variable, referrer), stack)?,
+          format!("variable {}", variable.identifier),
+        ),
+        ColorSetValue::Reference(reference) => (
+          self.resolve_reference_within(reference, self.reference_depth(referrer), stack)?,
+          format!("reference &{}", reference.path),
+        ),
+      };
 
-    let dark = match &colorset.dark {
-      ColorSetValue::Color(color) => Ok(color.clone()),
-      ColorSetValue::Variable(ref dark_variable) => match self.resolve(dark_variable)? {
+      match resolved {
         ResolvedVariable::Color(color) => Ok(color),
         ResolvedVariable::ColorSet(_) => Err(Error::ResolveError {
           message: format!(
-            "Attempt to assign a colorset to the dark property of another colorset via variable {}.",
-            dark_variable.identifier
-          )
+            "Attempt to assign a colorset to the {} property of another colorset via {}.",
+            property, source
+          ),
+          span: None,
         }),
-      },
-    }?;
+      }
+    };
 
+    let light = resolve(&colorset.light, "light")?;
+    let dark = resolve(&colorset.dark, "dark")?;
     Ok(ResolvedColorSet { light, dark })
   }
+
+  /// The number of scopes visible to `variable`. See `resolve_value` for `referrer`.
+  fn variable_depth(&self, variable: &Variable, referrer: Option<(&str, usize)>) -> usize {
+    match referrer {
+      Some((referrer, idx)) => variable_depth(variable, referrer, idx),
+      None => self.scopes.len(),
+    }
+  }
+
+  /// The number of scopes a reference is looked up in. See `resolve_value` for `referrer`.
+  fn reference_depth(&self, referrer: Option<(&str, usize)>) -> usize {
+    match referrer {
+      Some((_, idx)) => idx + 1,
+      None => self.scopes.len(),
+    }
+  }
+
+  fn path(&self) -> Vec<&'a str> {
+    self
+      .scopes
+      .last()
+      .map_or_else(Vec::new, |scope| scope.path.clone())
+  }
 }
 
 impl<'a> VarContext<'a> {
   pub fn derive_from(doc: &'a Document) -> Self {
-    let map: HashMap<String, &Value> = doc
+    let variables = doc
       .items
       .iter()
       .filter_map(|item| match item {
         DocumentItem::Variable(v) => Some(v),
         _ => None,
       })
-      .map(|variable| (variable.identifier.to_string(), &variable.value))
+      .map(|variable| (variable.identifier.as_str(), variable))
       .collect();
-    VarContext { map }
+    VarContext {
+      scopes: vec![Rc::new(Scope {
+        path: vec![],
+        items: Items::Document(&doc.items),
+        variables,
+      })],
+    }
+  }
+
+  /// Returns the context for the items of `ruleset`, which adds the variables declared in the
+  /// ruleset to the variables visible to the ruleset itself.
+  pub fn scoped(&self, ruleset: &'a RuleSet) -> Self {
+    let variables = ruleset
+      .items
+      .iter()
+      .filter_map(|item| match item {
+        RuleSetItem::Variable(v) => Some(v),
+        _ => None,
+      })
+      .map(|variable| (variable.identifier.as_str(), variable))
+      .collect();
+    let mut path = self.path();
+    path.push(&ruleset.identifier);
+
+    let mut scopes = self.scopes.clone();
+    scopes.push(Rc::new(Scope {
+      path,
+      items: Items::RuleSet(&ruleset.items),
+      variables,
+    }));
+    VarContext { scopes }
+  }
+}
+
+impl<'a> Items<'a> {
+  /// Returns the ruleset or declaration named `identifier`.
+  pub(crate) fn child(self, identifier: &str) -> Option<Target<'a>> {
+    match self {
+      Items::Document(items) => items.iter().find_map(|item| match item {
+        DocumentItem::RuleSet(ruleset) if ruleset.identifier == identifier => {
+          Some(Target::RuleSet(ruleset))
+        }
+        DocumentItem::Declaration(decl) if decl.identifier == identifier => {
+          Some(Target::Declaration(decl))
+        }
+        _ => None,
+      }),
+      Items::RuleSet(items) => items.iter().find_map(|item| match item {
+        RuleSetItem::RuleSet(ruleset) if ruleset.identifier == identifier => {
+          Some(Target::RuleSet(ruleset))
+        }
+        RuleSetItem::Declaration(decl) if decl.identifier == identifier => {
+          Some(Target::Declaration(decl))
+        }
+        _ => None,
+      }),
+    }
+  }
+}
+
+/// Runs `resolve` with `declaration` pushed onto `stack`. Fails if `declaration` is already being
+/// resolved, i.e. it refers to itself.
+fn with_entry<'a, T>(
+  stack: &mut Stack<'a>,
+  name: String,
+  declaration: &'a Declaration<Value>,
+  resolve: impl FnOnce(&mut Stack<'a>) -> Result<T, Error>,
+) -> Result<T, Error> {
+  if let Some(idx) = stack
+    .iter()
+    .position(|(_, entry)| ptr::eq(*entry, declaration))
+  {
+    let cycle: Vec<&str> = stack[idx..]
+      .iter()
+      .map(|(name, _)| name.as_str())
+      .chain([name.as_str()])
+      .collect();
+    return Err(Error::ResolveError {
+      message: format!("Circular reference: {}.", cycle.join(" → ")),
+      span: None,
+    });
+  }
+
+  stack.push((name, declaration));
+  let result = resolve(stack).map_err(|e| e.located_at(declaration.span));
+  stack.pop();
+  result
+}
+
+fn with_opacity(color: Color, reference: &Reference) -> Color {
+  Color {
+    a: color.a * reference.opacity,
+    ..color
+  }
+}
+
+/// The number of scopes visible to `variable` in the value of the variable named `referrer`, which
+/// is declared in the scope at `idx`. A variable referring to its own name refers to the variable of
+/// an enclosing scope, e.g. `$accent: $accent 50%`.
+fn variable_depth(variable: &Variable, referrer: &str, idx: usize) -> usize {
+  if variable.identifier == referrer {
+    idx
+  } else {
+    idx + 1
   }
 }
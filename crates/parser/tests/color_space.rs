@@ -0,0 +1,218 @@
+use parser::ast::Color;
+use parser::color_space::{
+  color_difference, composite, contrast_ratio, display_p3_components, hex_string,
+  is_in_display_p3_gamut, is_same_color, linear_srgb_components, relative_luminance, Lab, Lch,
+  Oklab, Oklch, BLACK, WHITE,
+};
+
+// The reference values are taken from the examples of CSS Color Module Level 4.
+
+#[test]
+fn oklab_from_srgb() {
+  let cases = [
+    (0xffffff, [1.0, 0.0, 0.0]),
+    (0x000000, [0.0, 0.0, 0.0]),
+    (0xff0000, [0.627955, 0.224863, 0.125846]),
+    (0x00ff00, [0.866440, -0.233888, 0.179498]),
+    (0x0000ff, [0.452014, -0.032457, -0.311528]),
+  ];
+
+  for (rgb, [l, a, b]) in cases {
+    let lab = Oklab::from_color(&color(rgb));
+    assert_close(lab.l, l, 0.0001, rgb);
+    assert_close(lab.a, a, 0.0001, rgb);
+    assert_close(lab.b, b, 0.0001, rgb);
+  }
+}
+
+#[test]
+fn oklch_from_srgb() {
+  let lch = Oklch::from_color(&color(0xff0000));
+  assert_close(lch.l, 0.627955, 0.0001, 0xff0000);
+  assert_close(lch.c, 0.257683, 0.0001, 0xff0000);
+  assert_close(lch.h, 29.2339, 0.01, 0xff0000);
+
+  let lch = Oklch::from_color(&color(0x0000ff));
+  assert_close(lch.h, 264.052, 0.01, 0x0000ff);
+}
+
+#[test]
+fn lab_from_srgb() {
+  let cases = [
+    (0xffffff, [100.0, 0.0, 0.0]),
+    (0xff0000, [54.2905, 80.8049, 69.891]),
+    (0x00ff00, [87.8185, -79.2711, 80.9946]),
+    (0x0000ff, [29.5683, 68.2874, -112.0297]),
+  ];
+
+  for (rgb, [l, a, b]) in cases {
+    let lab = Lab::from_color(&color(rgb));
+    assert_close(lab.l, l, 0.01, rgb);
+    assert_close(lab.a, a, 0.01, rgb);
+    assert_close(lab.b, b, 0.01, rgb);
+  }
+
+  let lch = Lch::from_color(&color(0xff0000));
+  assert_close(lch.c, 106.8371, 0.01, 0xff0000);
+  assert_close(lch.h, 40.8526, 0.01, 0xff0000);
+}
+
+#[test]
+fn roundtrip() {
+  for rgb in [0xffffff, 0x000000, 0xff0000, 0x1e6fff, 0x5753cf, 0x85868a] {
+    let expected = (color(rgb), false);
+    assert_eq!(Oklch::from_color(&color(rgb)).to_color(1.0), expected);
+    assert_eq!(Oklab::from_color(&color(rgb)).to_color(1.0), expected);
+    assert_eq!(Lch::from_color(&color(rgb)).to_color(1.0), expected);
+    assert_eq!(Lab::from_color(&color(rgb)).to_color(1.0), expected);
+  }
+}
+
+#[test]
+fn gamut_mapping() {
+  // A green more saturated than sRGB can represent keeps its lightness and hue.
+  let green = Oklch {
+    l: 0.7,
+    c: 0.4,
+    h: 145.0,
+  };
+  let (mapped, clipped) = green.to_color(0.5);
+  assert!(clipped);
+  assert_eq!(mapped.a, 0.5);
+  let lch = Oklch::from_color(&mapped);
+  assert_close(lch.l, 0.7, 0.01, 0);
+  assert_close(lch.h, 145.0, 1.0, 0);
+  assert!(lch.c < 0.4);
+
+  let (_, clipped) = Lch {
+    l: 50.0,
+    c: 150.0,
+    h: 0.0,
+  }
+  .to_color(1.0);
+  assert!(clipped);
+
+  let (white, clipped) = Oklab {
+    l: 1.2,
+    a: 0.0,
+    b: 0.0,
+  }
+  .to_color(1.0);
+  assert!(clipped);
+  assert!(is_same_color(&white, &color(0xffffff)));
+}
+
+#[test]
+fn display_p3_from_srgb() {
+  let cases = [
+    (0xffffff, [1.0, 1.0, 1.0]),
+    (0x000000, [0.0, 0.0, 0.0]),
+    (0xff0000, [0.917488, 0.200287, 0.138561]),
+    (0x00ff00, [0.458402, 0.985265, 0.298295]),
+    (0x0000ff, [0.0, 0.0, 0.959588]),
+  ];
+
+  for (rgb, expected) in cases {
+    // Display P3 contains all of sRGB.
+    assert!(is_in_display_p3_gamut(&color(rgb)));
+    let components = display_p3_components(&color(rgb));
+    for (actual, expected) in components.into_iter().zip(expected) {
+      assert_close(actual, expected, 0.0001, rgb);
+    }
+  }
+}
+
+#[test]
+fn wide_gamut_colors_in_display_p3() {
+  let (mapped, clipped) = Oklch {
+    l: 0.65,
+    c: 0.26,
+    h: 145.0,
+  }
+  .to_color(1.0);
+  assert!(clipped);
+  assert!(mapped.linear.is_some());
+
+  // The original color is converted rather than the one mapped into sRGB.
+  assert!(is_in_display_p3_gamut(&mapped));
+  let expected = [0.169567, 0.691012, 0.114017];
+  for (actual, expected) in display_p3_components(&mapped).into_iter().zip(expected) {
+    assert_close(actual, expected, 0.0001, 0x00ad2e);
+  }
+  assert!(display_p3_components(&color(0x00ad2e))[0] > 0.2);
+
+  let (mapped, _) = Oklch {
+    l: 0.65,
+    c: 0.3,
+    h: 145.0,
+  }
+  .to_color(1.0);
+  assert!(!is_in_display_p3_gamut(&mapped));
+}
+
+#[test]
+fn linear_srgb() {
+  let [r, g, b] = linear_srgb_components(&color(0xff8000));
+  assert_close(r, 1.0, 0.0001, 0xff8000);
+  assert_close(g, 0.215861, 0.0001, 0xff8000);
+  assert_close(b, 0.0, 0.0001, 0xff8000);
+}
+
+#[test]
+fn contrast_ratios() {
+  assert_eq!(relative_luminance(&BLACK), 0.0);
+  assert_eq!(relative_luminance(&WHITE), 1.0);
+  assert!((contrast_ratio(&BLACK, &WHITE) - 21.0).abs() < 0.001);
+  assert!((contrast_ratio(&WHITE, &BLACK) - 21.0).abs() < 0.001);
+  assert!((contrast_ratio(&color(0x777777), &WHITE) - 4.48).abs() < 0.01);
+}
+
+#[test]
+fn composite_colors() {
+  let translucent = |rgb: u32, a: f32| Color { a, ..color(rgb) };
+
+  assert_eq!(
+    composite(&translucent(0x000000, 0.5), &WHITE),
+    color(0x808080)
+  );
+  assert_eq!(composite(&WHITE, &BLACK), WHITE);
+  assert_eq!(composite(&translucent(0xffffff, 0.0), &BLACK), BLACK);
+}
+
+#[test]
+fn color_differences() {
+  assert!((color_difference(&BLACK, &WHITE) - 100.0).abs() < 0.01);
+  assert_eq!(color_difference(&WHITE, &WHITE), 0.0);
+  assert!(color_difference(&color(0x151618), &color(0x151719)) < 1.0);
+}
+
+#[test]
+fn hex_strings() {
+  assert_eq!(hex_string(&color(0x151618)), "#151618");
+  assert_eq!(hex_string(&Color { a: 0.5, ..BLACK }), "#000000 50%");
+  assert!(is_same_color(
+    &Color { a: 0.5, ..BLACK },
+    &Color { a: 0.5004, ..BLACK }
+  ));
+  assert!(!is_same_color(&color(0x151618), &color(0x151619)));
+}
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64, rgb: u32) {
+  assert!(
+    (actual - expected).abs() <= tolerance,
+    "Expected {} for #{:06x}, got {}",
+    expected,
+    rgb,
+    actual
+  );
+}
+
+fn color(rgb: u32) -> Color {
+  Color {
+    r: (rgb >> 16) as u8,
+    g: (rgb >> 8) as u8,
+    b: rgb as u8,
+    a: 1.0,
+    linear: None,
+  }
+}
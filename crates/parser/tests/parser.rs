@@ -1,5 +1,5 @@
 use insta::{assert_debug_snapshot, assert_snapshot};
-use parser::parse_document;
+use parser::{gamut_warnings, parse_document, Error, Gamut};
 
 #[test]
 fn empty_document() {
@@ -182,6 +182,74 @@ fn comments() {
   );
 }
 
+#[test]
+fn doc_comments() {
+  test_parse_doc(
+    "doc_comments_0",
+    r#"
+    /// Pure black
+    $black: #000000
+
+    /// Text colors
+    // Not part of the documentation
+    Text {
+      /// Used for body text.
+      ///
+      ///Spans multiple lines.
+      Primary: $black // Trailing comment
+      //// Not a doc comment
+      Secondary: #ffffff
+      /// Dangling doc comment
+    }
+
+    /// Dotted identifiers keep their doc comments
+    Button.Primary: #ff0000
+    /// Dangling doc comment
+  "#,
+  );
+}
+
+#[test]
+fn annotations() {
+  test_parse_doc(
+    "annotations_0",
+    r#"
+    Text {
+      /// Use Text.Body instead.
+      @renamed(Text.Body)
+      Primary: #151618
+      @deprecated
+      Secondary: #85868A
+      @deprecated("Use Text.Body") @renamed( Text.Body )
+      Tertiary: #85868A
+      Body: #151618
+    }
+  "#,
+  );
+  test_parse_doc("annotations_1", "@unknown\nA: #ffffff");
+  test_parse_doc("annotations_2", "@deprecated(Text.Primary)\nA: #ffffff");
+  test_parse_doc("annotations_3", "@renamed\nA: #ffffff");
+}
+
+#[test]
+fn contrast_requirements() {
+  test_parse_doc(
+    "contrast_requirements_0",
+    r#"
+    Background: #ffffff
+    Text.Primary: #151618
+    // Body text
+    @contrast(Text.Primary on Background, AA)
+    @contrast( Text.Primary on Background , AAA-large )
+  "#,
+  );
+  test_parse_doc(
+    "contrast_requirements_1",
+    "@contrast(Text.Primary on Background, A)",
+  );
+  test_parse_doc("contrast_requirements_2", "@contrast(Text.Primary, AA)");
+}
+
 #[test]
 fn color_sets() {
   test_parse_doc(
@@ -237,6 +305,232 @@ fn typos_in_color_sets() {
   )
 }
 
+#[test]
+fn identifiers() {
+  test_parse_doc(
+    "identifiers_1",
+    r#"
+    $brand-blue: #1E6FFF
+    $grey_10: #EEEEEE
+    brand-primary: $brand-blue
+    Text_Secondary: (light: $grey_10, dark: $brand-blue 50%)
+  "#,
+  );
+  test_parse_doc("identifiers_2", "-a: #ffffff");
+  test_parse_doc("identifiers_3", "$-a: #ffffff");
+  test_parse_doc("identifiers_4", "a.: #ffffff");
+}
+
+#[test]
+fn dotted_identifiers() {
+  test_parse_doc(
+    "dotted_identifiers_1",
+    r#"
+    Button.Primary: #ff0000
+    Button.Secondary: #00ff00
+    Button {
+      Tertiary: #0000ff
+      Inner.Deep: #000000
+    }
+    Button.Inner {
+      Other: #ffffff
+    }
+  "#,
+  );
+}
+
+#[test]
+fn scoped_variables() {
+  test_parse_doc(
+    "scoped_variables",
+    r#"
+    $bg: #ffffff
+    NumericInput {
+      $bg: #cccccc
+      Background: $bg
+      Key.Pressed {
+        $pressed: $bg 50%
+        Background: $pressed
+      }
+    }
+  "#,
+  );
+}
+
+#[test]
+fn references() {
+  test_parse_doc(
+    "references_1",
+    r#"
+    NumericInput {
+      Background: #ffffff
+      Highlight: &Background 80%
+      Shadow: (light: &NumericInput.Background, dark: #000000)
+    }
+  "#,
+  );
+  test_parse_doc("references_2", "a: &");
+}
+
+#[test]
+fn extends() {
+  test_parse_doc(
+    "extends_1",
+    r#"
+    Button {
+      Background: #ffffff
+    }
+    PrimaryButton {
+      @extend Button
+      Text: #000000
+    }
+  "#,
+  );
+  test_parse_doc(
+    "extends_2",
+    r#"
+    PrimaryButton {
+      @extend
+    }
+  "#,
+  );
+}
+
+#[test]
+fn scales() {
+  test_parse_doc(
+    "scales_1",
+    r#"
+    $blue: scale(#1E6FFF, steps: 9, from: 10%, to: 90%)
+    $grey: scale(rgba(128, 128, 128, 0.5), to: 80%)
+    Text {
+      /// The brand colors.
+      $brand: scale(#FF0000)
+    }
+  "#,
+  );
+  test_parse_doc(
+    "scales_2",
+    r#"
+    $blue: scale(#1E6FFF, steps: 0)
+  "#,
+  );
+  test_parse_doc(
+    "scales_3",
+    r#"
+    $blue: scale(#1E6FFF, from: 10%, steps: 9)
+  "#,
+  );
+}
+
+#[test]
+fn color_functions() {
+  test_parse_doc(
+    "color_functions_1",
+    r#"
+    $accent: oklch(70% 0.1 250deg / 50%)
+    Text {
+      Primary: (light: oklab(0.45 -0.03 -0.31), dark: lch(54 80 40))
+      Accent: oklch(0.7 0.4 145)
+    }
+  "#,
+  );
+  test_parse_doc(
+    "color_functions_2",
+    r#"
+    Text: oklch(70% 0.1)
+  "#,
+  );
+}
+
+#[test]
+fn out_of_gamut_colors() {
+  let source = r#"
+    $accent: oklch(70% 0.1 250)
+    Text {
+      Primary: (light: oklch(0.7 0.4 145), dark: lch(50 150 0 / 0.5))
+      Secondary: oklab(1.2 0 0)
+      // Rounded, but not noticeably outside of the gamut
+      Tertiary: lch(54.29 106.84 40.85)
+    }
+  "#;
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+
+  let warnings: Vec<(usize, String)> = gamut_warnings(&doc, source, Gamut::SRGB)
+    .into_iter()
+    .map(|warning| (warning.span.line_column(source).0, warning.message))
+    .collect();
+  assert_eq!(
+    warnings,
+    vec![
+      (
+        4,
+        "oklch(0.7 0.4 145) is outside of the sRGB gamut and has been mapped to #00BF34."
+          .to_string()
+      ),
+      (
+        4,
+        "lch(50 150 0 / 0.5) is outside of the sRGB gamut and has been mapped to #F10083."
+          .to_string()
+      ),
+      (
+        5,
+        "oklab(1.2 0 0) is outside of the sRGB gamut and has been mapped to #FFFFFF.".to_string()
+      ),
+    ]
+  );
+}
+
+#[test]
+fn colors_outside_of_display_p3() {
+  let source = r#"
+    // Outside of sRGB, but within Display P3
+    Accent: oklch(65% 0.26 145)
+    Highlight: oklch(65% 0.3 145)
+  "#;
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+
+  let warnings: Vec<(usize, String)> = gamut_warnings(&doc, source, Gamut::DisplayP3)
+    .into_iter()
+    .map(|warning| (warning.span.line_column(source).0, warning.message))
+    .collect();
+  assert_eq!(
+    warnings,
+    vec![(
+      4,
+      "oklch(65% 0.3 145) is outside of the Display P3 gamut and has been clamped.".to_string()
+    )]
+  );
+  assert_eq!(gamut_warnings(&doc, source, Gamut::SRGB).len(), 2);
+}
+
+#[test]
+fn scale_steps_outside_of_srgb() {
+  let source = r#"
+    $grey: scale(#808080, steps: 3)
+    $green: scale(#00C800, steps: 3)
+  "#;
+  let doc = parse_document(source.to_string()).expect("Could not parse document");
+
+  let warnings: Vec<(usize, String)> = gamut_warnings(&doc, source, Gamut::DisplayP3)
+    .into_iter()
+    .map(|warning| (warning.span.line_column(source).0, warning.message))
+    .collect();
+  assert_eq!(
+    warnings,
+    vec![
+      (
+        3,
+        "$green200 is outside of the sRGB gamut and has been mapped to #007900.".to_string()
+      ),
+      (
+        3,
+        "$green300 is outside of the sRGB gamut and has been mapped to #000500.".to_string()
+      ),
+    ]
+  );
+}
+
 #[test]
 fn document() {
   test_parse_doc(
@@ -265,6 +559,25 @@ fn document() {
   );
 }
 
+#[test]
+fn error_locations() {
+  let source = "Text: #ffffff\nA {\n  B #ffffff\n}";
+  match parse_document(source.to_string()) {
+    Err(e @ Error::ParseError { .. }) => {
+      assert_eq!(e.span().map(|span| span.line_column(source)), Some((3, 5)));
+      if let Error::ParseError { summary, .. } = e {
+        assert_eq!(summary, "expected ':' in Declaration");
+      }
+    }
+    result => panic!("Expected a parse error, got {:?}", result),
+  }
+
+  match parse_document("@unknown\nA: #ffffff".to_string()) {
+    Err(Error::ParseError { summary, .. }) => assert_eq!(summary, "unexpected input in Annotation"),
+    result => panic!("Expected a parse error, got {:?}", result),
+  }
+}
+
 fn test_parse_doc(test_name: &str, contents: &str) {
   match parse_document(contents.to_string()) {
     Ok(doc) => assert_debug_snapshot!(test_name, doc),
@@ -0,0 +1,386 @@
+use parser::ast::Color;
+use parser::resolved::{Document, Item, Value};
+use parser::{parse_document, AssetNaming, Error};
+
+#[test]
+fn resolve_document() {
+  let doc = parse_document(
+    r#"
+    $black: #000000
+    $white: #ffffff
+    $ink: (light: $black, dark: $white 50%)
+    /// Body text
+    Text {
+      Primary: $ink
+      Secondary: (light: $black, dark: #333333)
+    }
+    Text.Link.Pressed: #0000ff
+    Background: $white
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+  let resolved = Document::derive_from(&doc).expect("Could not resolve document");
+
+  let paths: Vec<String> = resolved
+    .declarations()
+    .iter()
+    .map(|decl| decl.dotted_path())
+    .collect();
+  assert_eq!(
+    paths,
+    vec![
+      "Text.Primary",
+      "Text.Secondary",
+      "Text.Link.Pressed",
+      "Background"
+    ]
+  );
+
+  match &resolved.items[0] {
+    Item::RuleSet(ruleset) => {
+      assert_eq!(ruleset.path, vec!["Text"]);
+      assert_eq!(ruleset.doc, vec!["Body text"]);
+    }
+    other => panic!("Expected a ruleset, got {:?}", other),
+  }
+
+  let primary = resolved.find_declaration("Text.Primary").unwrap();
+  assert_eq!(primary.variable.as_deref(), Some("ink"));
+  assert_eq!(
+    primary.appearances(),
+    (&color(0x000000, 1.0), &color(0xffffff, 0.5))
+  );
+
+  let secondary = resolved.find_declaration("Text.Secondary").unwrap();
+  assert_eq!(secondary.variable, None);
+  match &secondary.value {
+    Value::ColorSet(colorset) => {
+      assert_eq!(colorset.light.variable.as_deref(), Some("black"));
+      assert_eq!(colorset.dark.variable, None);
+      assert_eq!(colorset.dark.color, color(0x333333, 1.0));
+    }
+    other => panic!("Expected a colorset, got {:?}", other),
+  }
+
+  let pressed = resolved.find_declaration("Text.Link.Pressed").unwrap();
+  assert_eq!(
+    pressed.asset_name(AssetNaming::Concatenated),
+    "TextLinkPressed"
+  );
+  assert_eq!(
+    pressed.asset_name(AssetNaming::Namespaced),
+    "Text/Link/Pressed"
+  );
+  assert_eq!(pressed.asset_name(AssetNaming::Dotted), "Text.Link.Pressed");
+
+  assert_eq!(
+    resolved.find_declaration("Background").unwrap().value,
+    Value::Color(color(0xffffff, 1.0))
+  );
+}
+
+#[test]
+fn resolve_scoped_variables() {
+  let doc = parse_document(
+    r#"
+    $bg: #ffffff
+    $accent: #0000ff
+    NumericInput {
+      $bg: #cccccc
+      $accent: $accent 50%
+      $key: (light: $bg, dark: $accent)
+      Background: $bg
+      Key {
+        $bg: #000000
+        Background: $bg
+        Face: $key
+      }
+      Accent: $accent
+    }
+    Background: $bg
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+  let resolved = Document::derive_from(&doc).expect("Could not resolve document");
+  let value = |path: &str| resolved.find_declaration(path).unwrap().appearances();
+
+  assert_eq!(value("NumericInput.Background").0, &color(0xcccccc, 1.0));
+  assert_eq!(
+    value("NumericInput.Key.Background").0,
+    &color(0x000000, 1.0)
+  );
+  // Variables are resolved in the scope they are declared in, not where they are used.
+  assert_eq!(
+    value("NumericInput.Key.Face"),
+    (&color(0xcccccc, 1.0), &color(0x0000ff, 0.5))
+  );
+  assert_eq!(value("NumericInput.Accent").0, &color(0x0000ff, 0.5));
+  assert_eq!(value("Background").0, &color(0xffffff, 1.0));
+}
+
+#[test]
+fn resolve_out_of_scope_variable() {
+  let doc = parse_document(
+    r#"
+    NumericInput {
+      $key: #cccccc
+      Background: $key
+    }
+    Keyboard: $key
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+  assert!(Document::derive_from(&doc).is_err());
+
+  let doc = parse_document("$a: $a\nText: $a".to_string()).expect("Could not parse document");
+  assert!(Document::derive_from(&doc).is_err());
+}
+
+#[test]
+fn resolve_references() {
+  let doc = parse_document(
+    r#"
+    $ink: (light: #000000, dark: #ffffff)
+    Background: #ffffff
+    NumericInput {
+      $key: #cccccc
+      Background: #eeeeee
+      Highlight: &Background 50%
+      DoneKey {
+        Background: $key
+        Label: &Text 50%
+        Border: (light: &Background, dark: &NumericInput.Background)
+      }
+      Pressed: &DoneKey.Background
+    }
+    Text: $ink
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+  let resolved = Document::derive_from(&doc).expect("Could not resolve document");
+  let value = |path: &str| resolved.find_declaration(path).unwrap().appearances();
+
+  // Paths are looked up relative to the enclosing ruleset first.
+  assert_eq!(value("NumericInput.Highlight").0, &color(0xeeeeee, 0.5));
+  assert_eq!(
+    value("NumericInput.DoneKey.Border"),
+    (&color(0xcccccc, 1.0), &color(0xeeeeee, 1.0))
+  );
+  // The referenced declaration is resolved with the variables visible to it.
+  assert_eq!(value("NumericInput.Pressed").0, &color(0xcccccc, 1.0));
+  // The opacity applies to both colors of a colorset.
+  assert_eq!(
+    value("NumericInput.DoneKey.Label"),
+    (&color(0x000000, 0.5), &color(0xffffff, 0.5))
+  );
+}
+
+#[test]
+fn resolve_invalid_references() {
+  let error = |source: &str| {
+    let doc = parse_document(source.to_string()).expect("Could not parse document");
+    match Document::derive_from(&doc) {
+      Err(Error::ResolveError { message, .. }) => message,
+      result => panic!("Expected a resolve error, got {:?}", result),
+    }
+  };
+
+  assert_eq!(
+    error("A: &B\nB: (light: &C, dark: #000000)\nC: &A"),
+    "Circular reference: B → C → A → B."
+  );
+  assert_eq!(error("A: &A"), "Circular reference: A → A.");
+  assert_eq!(
+    error("$a: $b\n$b: $a\nText: $a"),
+    "Circular reference: $a → $b → $a."
+  );
+  assert_eq!(
+    error("Text {\n  Primary: #000000\n}\nLabel: &Text"),
+    "&Text refers to a ruleset. References must refer to a declaration."
+  );
+  assert_eq!(
+    error("Text {\n  Primary: #000000\n}\nLabel: &Primary"),
+    "Could not find declaration with path Primary."
+  );
+}
+
+#[test]
+fn resolve_extends() {
+  let doc = parse_document(
+    r#"
+    Button {
+      $tint: #0000ff
+      Background: $tint
+      Highlight: &Background 50%
+      Text: #ffffff
+      Icon {
+        Tint: #ffffff
+        Shadow: #000000
+      }
+    }
+    PrimaryButton {
+      @extend Button
+      $tint: #00ff00
+      Text: #000000
+      Icon {
+        Tint: #000000
+      }
+    }
+    Buttons {
+      Destructive {
+        @extend PrimaryButton
+        Background: #ff0000
+      }
+    }
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+  let resolved = Document::derive_from(&doc).expect("Could not resolve document");
+
+  let paths: Vec<String> = resolved
+    .declarations()
+    .iter()
+    .map(|decl| decl.dotted_path())
+    .filter(|path| !path.starts_with("Button."))
+    .collect();
+  assert_eq!(
+    paths,
+    vec![
+      "PrimaryButton.Background",
+      "PrimaryButton.Highlight",
+      "PrimaryButton.Text",
+      "PrimaryButton.Icon.Shadow",
+      "PrimaryButton.Icon.Tint",
+      "Buttons.Destructive.Highlight",
+      "Buttons.Destructive.Text",
+      "Buttons.Destructive.Icon.Shadow",
+      "Buttons.Destructive.Icon.Tint",
+      "Buttons.Destructive.Background",
+    ]
+  );
+
+  let value = |path: &str| resolved.find_declaration(path).unwrap().appearances().0;
+  // Inherited declarations are resolved in the extending ruleset.
+  assert_eq!(value("Button.Highlight"), &color(0x0000ff, 0.5));
+  assert_eq!(value("PrimaryButton.Highlight"), &color(0x00ff00, 0.5));
+  assert_eq!(
+    value("Buttons.Destructive.Highlight"),
+    &color(0xff0000, 0.5)
+  );
+  assert_eq!(value("PrimaryButton.Icon.Tint"), &color(0x000000, 1.0));
+}
+
+#[test]
+fn resolve_invalid_extends() {
+  let error = |source: &str| {
+    let doc = parse_document(source.to_string()).expect("Could not parse document");
+    match Document::derive_from(&doc) {
+      Err(Error::ResolveError { message, .. }) => message,
+      result => panic!("Expected a resolve error, got {:?}", result),
+    }
+  };
+
+  assert_eq!(
+    error("A {\n  @extend B\n}\nB {\n  @extend A\n}"),
+    "Circular @extend: A → B → A."
+  );
+  assert_eq!(
+    error("A {\n  B {\n    @extend A\n  }\n}"),
+    "Circular @extend: A → A.B → A."
+  );
+  assert_eq!(
+    error("A {\n  @extend Missing\n}"),
+    "Could not find ruleset Missing extended by A."
+  );
+  assert_eq!(
+    error("Text: #000000\nA {\n  @extend Text\n}"),
+    "@extend Text in A refers to a declaration. Only rulesets can be extended."
+  );
+}
+
+#[test]
+fn resolve_scales() {
+  let doc = parse_document(
+    r#"
+    $grey: scale(#808080, steps: 3, from: 0%, to: 100%)
+    $blue: scale(#1E6FFF 50%, steps: 5)
+    White: $grey100
+    Grey: $grey200
+    Black: $grey300
+    Button {
+      $blue: scale(#1E6FFF, steps: 2, from: 40%, to: 60%)
+      Background: $blue200
+      Highlight: $blue500
+    }
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+  let resolved = Document::derive_from(&doc).expect("Could not resolve document");
+
+  let value = |path: &str| {
+    let decl = resolved
+      .declarations()
+      .into_iter()
+      .find(|decl| decl.dotted_path() == path)
+      .unwrap_or_else(|| panic!("Missing declaration {}", path));
+    match &decl.value {
+      Value::Color(color) => color.clone(),
+      value => panic!("Expected a color, got {:?}", value),
+    }
+  };
+
+  assert_eq!(value("White"), color(0xffffff, 1.0));
+  assert_eq!(value("Grey"), color(0x636363, 1.0));
+  assert_eq!(value("Black"), color(0x000000, 1.0));
+  assert_eq!(value("Button.Background"), color(0x002fbe, 1.0));
+  assert_eq!(value("Button.Highlight"), color(0x000215, 0.5));
+
+  let doc = parse_document(
+    "$blue: scale(#1E6FFF, steps: 3)
+Text: $blue400"
+      .to_string(),
+  )
+  .expect("Could not parse document");
+  assert!(Document::derive_from(&doc).is_err());
+}
+
+#[test]
+fn resolve_unknown_variable() {
+  let doc = parse_document("Text: $missing".to_string()).expect("Could not parse document");
+  assert!(Document::derive_from(&doc).is_err());
+}
+
+fn color(rgb: u32, a: f32) -> Color {
+  Color {
+    r: (rgb >> 16) as u8,
+    g: (rgb >> 8) as u8,
+    b: rgb as u8,
+    a,
+    linear: None,
+  }
+}
+
+#[test]
+fn locate_resolve_errors() {
+  let location = |source: &str| {
+    let doc = parse_document(source.to_string()).expect("Could not parse document");
+    match Document::derive_from(&doc) {
+      Err(e @ Error::ResolveError { .. }) => e.span().map(|span| span.line_column(source)),
+      result => panic!("Expected a resolve error, got {:?}", result),
+    }
+  };
+
+  assert_eq!(location("A: #000000\nB: $missing"), Some((2, 1)));
+  assert_eq!(location("$c: $d\n$d: $c\nA: $c"), Some((2, 1)));
+  assert_eq!(
+    location("Text {\n  Primary: (light: &Missing, dark: #000000)\n}"),
+    Some((2, 3))
+  );
+  assert_eq!(location("A {\n  @extend B\n}"), Some((2, 3)));
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "serde")]
+
+use parser::ast::Document;
+use parser::parse_document;
+use parser::resolved::Document as ResolvedDocument;
+
+const STYLESHEET: &str = r#"
+  $black: #000000
+  $white: #ffffff
+  $ink: (light: $black, dark: $white 50%)
+  /// Body text
+  Text {
+    /// The default text color
+    @deprecated("Use Text.Body")
+    Primary: $ink
+    @renamed(Text.Muted)
+    Secondary: (light: $black 33%, dark: #333333)
+    Link {
+      Pressed: rgba(0, 0, 255, 0.7)
+    }
+  }
+  Background: $white
+  Highlight: &Text.Link.Pressed 80%
+  @contrast(Text.Primary on Background, AA-large)
+"#;
+
+#[test]
+fn document_roundtrip() {
+  let doc = parse_document(STYLESHEET.to_string()).expect("Could not parse document");
+  let json = serde_json::to_string(&doc).unwrap();
+  let decoded: Document = serde_json::from_str(&json).unwrap();
+  assert_eq!(decoded, doc);
+}
+
+#[test]
+fn resolved_document_roundtrip() {
+  let doc = parse_document(STYLESHEET.to_string()).expect("Could not parse document");
+  let resolved = ResolvedDocument::derive_from(&doc).expect("Could not resolve document");
+  let json = serde_json::to_string(&resolved).unwrap();
+  let decoded: ResolvedDocument = serde_json::from_str(&json).unwrap();
+  assert_eq!(decoded, resolved);
+}
+
+#[test]
+fn serialized_schema() {
+  let doc = parse_document(
+    r#"
+    $white: #ffffff
+    @renamed(Background)
+    Surface: $white 50%
+    @contrast(Surface on Surface, AAA-large)
+  "#
+    .to_string(),
+  )
+  .expect("Could not parse document");
+
+  assert_eq!(
+    serde_json::to_value(&doc.items[1]).unwrap(),
+    serde_json::json!({
+      "type": "declaration",
+      "identifier": "Surface",
+      "value": { "type": "variable", "identifier": "white", "opacity": 0.5 },
+      "doc": [],
+      "annotations": [{ "type": "renamed", "identifier": "Background" }],
+      "span": { "start": 50, "end": 69 }
+    })
+  );
+  assert_eq!(
+    serde_json::to_value(&doc.items[2]).unwrap(),
+    serde_json::json!({
+      "type": "contrast",
+      "foreground": "Surface",
+      "background": "Surface",
+      "level": "AAA-large",
+      "span": { "start": 74, "end": 114 }
+    })
+  );
+}
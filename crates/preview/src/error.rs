@@ -0,0 +1,9 @@
+use std::io;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+  #[error(transparent)]
+  Io(#[from] io::Error),
+  #[error(transparent)]
+  Parser(#[from] parser::Error),
+}
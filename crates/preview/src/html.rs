@@ -0,0 +1,141 @@
+use super::palette::{escape, Entry, Group, Sample, Swatch};
+
+const STYLE: &str = r#"body {
+  margin: 0;
+  padding: 32px;
+  background: #f2f2f7;
+  color: #1c1c1e;
+  font: 14px/1.4 -apple-system, BlinkMacSystemFont, "Helvetica Neue", Helvetica, Arial, sans-serif;
+}
+section {
+  margin: 24px 0 0 16px;
+}
+h1, h2 {
+  margin: 0 0 8px;
+}
+.doc {
+  margin: 0 0 8px;
+  color: #636366;
+}
+.swatch {
+  display: flex;
+  align-items: stretch;
+  margin: 8px 0;
+  background: #ffffff;
+  border-radius: 8px;
+  overflow: hidden;
+  box-shadow: 0 1px 2px rgba(0, 0, 0, 0.15);
+}
+.swatch .name {
+  flex: 1;
+  padding: 12px;
+}
+.swatch .name code {
+  font-weight: 600;
+}
+.deprecated .name code {
+  text-decoration: line-through;
+}
+.appearance {
+  display: flex;
+  align-items: center;
+  width: 280px;
+  padding: 12px;
+}
+.appearance.light {
+  background: #ffffff;
+  color: #1c1c1e;
+}
+.appearance.dark {
+  background: #000000;
+  color: #f2f2f7;
+}
+.chip {
+  flex: none;
+  width: 48px;
+  height: 48px;
+  margin-right: 12px;
+  border-radius: 6px;
+  box-shadow: inset 0 0 0 1px rgba(127, 127, 127, 0.4);
+}
+.details {
+  display: flex;
+  flex-direction: column;
+}
+.variable, .contrast {
+  font-size: 12px;
+  opacity: 0.7;
+}"#;
+
+/// Renders `palette` as a self-contained HTML page.
+pub fn render(palette: &Group) -> String {
+  let mut html = String::new();
+  html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+  html.push_str("<title>Colors</title>\n");
+  html.push_str(&format!("<style>\n{}\n</style>\n", STYLE));
+  html.push_str("</head>\n<body>\n");
+  html.push_str(&format!(
+    "<h1>Colors</h1>\n<p class=\"doc\">{} colors. Contrast ratios are measured against white in light and black in dark mode.</p>\n",
+    palette.swatch_count()
+  ));
+  render_entries(&palette.entries, &mut html);
+  html.push_str("</body>\n</html>\n");
+  html
+}
+
+fn render_entries(entries: &[Entry], html: &mut String) {
+  for entry in entries {
+    match entry {
+      Entry::Swatch(swatch) => render_swatch(swatch, html),
+      Entry::Group(group) => {
+        html.push_str("<section>\n");
+        html.push_str(&format!("<h2>{}</h2>\n", escape(&group.path)));
+        render_doc(&group.doc, html);
+        render_entries(&group.entries, html);
+        html.push_str("</section>\n");
+      }
+    }
+  }
+}
+
+fn render_swatch(swatch: &Swatch, html: &mut String) {
+  html.push_str(&format!(
+    "<div class=\"swatch{}\">\n<div class=\"name\">\n<code>{}</code>\n",
+    if swatch.deprecated { " deprecated" } else { "" },
+    escape(&swatch.path)
+  ));
+  render_doc(&swatch.doc, html);
+  html.push_str("</div>\n");
+  render_sample("light", &swatch.light, html);
+  render_sample("dark", &swatch.dark, html);
+  html.push_str("</div>\n");
+}
+
+fn render_sample(appearance: &str, sample: &Sample, html: &mut String) {
+  html.push_str(&format!(
+    "<div class=\"appearance {}\">\n<div class=\"chip\" style=\"background: {}\"></div>\n<div class=\"details\">\n<code>{}</code>\n",
+    appearance,
+    sample.css_color(),
+    escape(&sample.hex)
+  ));
+  if let Some(variable) = &sample.variable {
+    html.push_str(&format!(
+      "<span class=\"variable\">${}</span>\n",
+      escape(variable)
+    ));
+  }
+  html.push_str(&format!(
+    "<span class=\"contrast\">{:.2}:1</span>\n</div>\n</div>\n",
+    sample.contrast
+  ));
+}
+
+fn render_doc(doc: &[String], html: &mut String) {
+  if doc.is_empty() {
+    return;
+  }
+  html.push_str(&format!(
+    "<p class=\"doc\">{}</p>\n",
+    escape(&doc.join(" "))
+  ));
+}
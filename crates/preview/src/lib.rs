@@ -0,0 +1,63 @@
+mod error;
+mod html;
+mod palette;
+mod svg;
+
+pub use self::error::Error;
+
+use self::palette::Group;
+use file_tree::{write_file_atomically, Change};
+use parser::ast::Document;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The file format of the preview.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Format {
+  /// A self-contained HTML page.
+  #[default]
+  Html,
+  /// A standalone SVG image.
+  Svg,
+}
+
+impl FromStr for Format {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Format, ()> {
+    match s.to_lowercase().as_ref() {
+      "html" => Ok(Format::Html),
+      "svg" => Ok(Format::Svg),
+      _ => Err(()),
+    }
+  }
+}
+
+/// Renders a swatch sheet of every declaration of `doc`, grouped by rulesets. Each swatch shows
+/// the light and dark color with their hex value, the variable they have been taken from and their
+/// contrast ratio against the default background of the appearance. The output references no
+/// external resources.
+pub fn render_preview(doc: &Document, format: Format) -> Result<String, Error> {
+  let palette = Group::derive_from(doc)?;
+  Ok(match format {
+    Format::Html => html::render(&palette),
+    Format::Svg => svg::render(&palette),
+  })
+}
+
+/// Renders the preview of `doc` and writes it to `path`, see `render_preview`. Returns the change
+/// made to `path`, which is left untouched if its contents remain identical.
+pub fn write_preview(
+  doc: &Document,
+  path: impl AsRef<Path>,
+  format: Format,
+) -> Result<Vec<Change>, Error> {
+  let path = path.as_ref();
+  let contents = render_preview(doc, format)?;
+  let change = Change::for_file(path, contents.as_bytes())?;
+
+  if change.is_some() {
+    write_file_atomically(path, contents.as_bytes())?;
+  }
+  Ok(change.into_iter().collect())
+}
@@ -0,0 +1,152 @@
+//! The swatches shown by the preview, grouped like the rulesets of the stylesheet.
+
+use super::Error;
+use parser::ast::{Annotation, Color, Document};
+use parser::color_space::{composite, contrast_ratio, hex_string, BLACK, WHITE};
+use parser::resolved::{
+  Declaration, Document as ResolvedDocument, Item as ResolvedItem, SourcedColor, Value,
+};
+
+/// A ruleset, or the stylesheet itself for the top-level group.
+pub struct Group {
+  /// The dot-separated path of the ruleset, empty for the top-level group.
+  pub path: String,
+  pub doc: Vec<String>,
+  pub entries: Vec<Entry>,
+}
+
+pub enum Entry {
+  Swatch(Swatch),
+  Group(Group),
+}
+
+pub struct Swatch {
+  /// The dot-separated path of the declaration, e.g. `Text.Primary`.
+  pub path: String,
+  pub doc: Vec<String>,
+  pub deprecated: bool,
+  pub light: Sample,
+  pub dark: Sample,
+}
+
+/// The color of a declaration in one appearance.
+pub struct Sample {
+  pub color: Color,
+  /// The color in the notation of the stylesheet, e.g. `#151618` or `#000000 50%`.
+  pub hex: String,
+  /// The variable the color has been taken from.
+  pub variable: Option<String>,
+  /// The contrast ratio against the default background of the appearance, white for light and
+  /// black for dark.
+  pub contrast: f32,
+}
+
+impl Group {
+  pub fn derive_from(doc: &Document) -> Result<Self, Error> {
+    let doc = ResolvedDocument::derive_from(doc)?;
+    Ok(Group {
+      path: String::new(),
+      doc: vec![],
+      entries: entries(&doc.items),
+    })
+  }
+
+  /// The number of swatches in the group and its nested groups.
+  pub fn swatch_count(&self) -> usize {
+    self
+      .entries
+      .iter()
+      .map(|entry| match entry {
+        Entry::Swatch(_) => 1,
+        Entry::Group(group) => group.swatch_count(),
+      })
+      .sum()
+  }
+}
+
+fn entries(items: &[ResolvedItem]) -> Vec<Entry> {
+  items
+    .iter()
+    .map(|item| match item {
+      ResolvedItem::Declaration(decl) => Entry::Swatch(Swatch::derive_from(decl)),
+      ResolvedItem::RuleSet(ruleset) => Entry::Group(Group {
+        path: ruleset.path.join("."),
+        doc: ruleset.doc.clone(),
+        entries: entries(&ruleset.items),
+      }),
+    })
+    .collect()
+}
+
+impl Swatch {
+  fn derive_from(decl: &Declaration) -> Self {
+    let (light, dark) = match &decl.value {
+      Value::Color(color) => (color, color),
+      Value::ColorSet(colorset) => (&colorset.light.color, &colorset.dark.color),
+    };
+    // Colors and colorsets assigned as a whole remember their variable on the declaration.
+    let variable = |sourced: Option<&SourcedColor>| {
+      sourced
+        .and_then(|sourced| sourced.variable.clone())
+        .or_else(|| decl.variable.clone())
+    };
+    let (light_variable, dark_variable) = match &decl.value {
+      Value::Color(_) => (variable(None), variable(None)),
+      Value::ColorSet(colorset) => (
+        variable(Some(&colorset.light)),
+        variable(Some(&colorset.dark)),
+      ),
+    };
+
+    Swatch {
+      path: decl.dotted_path(),
+      doc: decl.doc.clone(),
+      deprecated: decl.annotations.iter().any(|annotation| {
+        matches!(
+          annotation,
+          Annotation::Deprecated { .. } | Annotation::Renamed { .. }
+        )
+      }),
+      light: Sample::new(light, light_variable, &WHITE),
+      dark: Sample::new(dark, dark_variable, &BLACK),
+    }
+  }
+}
+
+impl Sample {
+  fn new(color: &Color, variable: Option<String>, background: &Color) -> Self {
+    Sample {
+      color: color.clone(),
+      hex: hex_string(color),
+      variable,
+      contrast: contrast_ratio(&composite(color, background), background),
+    }
+  }
+
+  /// The color as a CSS color, e.g. `rgba(21, 22, 24, 0.5)`.
+  pub fn css_color(&self) -> String {
+    format!(
+      "rgba({}, {}, {}, {})",
+      self.color.r,
+      self.color.g,
+      self.color.b,
+      (self.color.a * 1000.0).round() / 1000.0
+    )
+  }
+}
+
+/// Escapes `text` for use in HTML and SVG text and attribute values.
+pub fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
@@ -0,0 +1,115 @@
+use super::palette::{escape, Entry, Group, Sample, Swatch};
+
+const WIDTH: u32 = 980;
+const MARGIN: u32 = 24;
+const INDENT: u32 = 16;
+const HEADING_HEIGHT: u32 = 36;
+const SWATCH_HEIGHT: u32 = 64;
+const PANEL_WIDTH: u32 = 270;
+const LIGHT_PANEL_X: u32 = WIDTH - MARGIN - 2 * PANEL_WIDTH;
+const DARK_PANEL_X: u32 = WIDTH - MARGIN - PANEL_WIDTH;
+const FONT: &str = "-apple-system, BlinkMacSystemFont, Helvetica, Arial, sans-serif";
+
+/// Renders `palette` as a standalone SVG image.
+pub fn render(palette: &Group) -> String {
+  let mut body = String::new();
+  let mut y = MARGIN;
+
+  body.push_str(&format!(
+    "<text x=\"{}\" y=\"{}\" font-size=\"24\" font-weight=\"bold\">Colors</text>\n",
+    MARGIN,
+    y + 24
+  ));
+  y += HEADING_HEIGHT + 8;
+  render_entries(&palette.entries, 0, &mut y, &mut body);
+
+  let height = y + MARGIN;
+  format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" font-family=\"{font}\" font-size=\"14\" fill=\"#1C1C1E\">\n<rect width=\"{width}\" height=\"{height}\" fill=\"#F2F2F7\"/>\n{body}</svg>\n",
+    width = WIDTH,
+    height = height,
+    font = FONT,
+    body = body
+  )
+}
+
+fn render_entries(entries: &[Entry], depth: u32, y: &mut u32, svg: &mut String) {
+  for entry in entries {
+    match entry {
+      Entry::Swatch(swatch) => render_swatch(swatch, depth, y, svg),
+      Entry::Group(group) => {
+        svg.push_str(&format!(
+          "<text x=\"{}\" y=\"{}\" font-size=\"18\" font-weight=\"bold\">{}</text>\n",
+          MARGIN + depth * INDENT,
+          *y + 24,
+          escape(&group.path)
+        ));
+        *y += HEADING_HEIGHT;
+        render_entries(&group.entries, depth + 1, y, svg);
+      }
+    }
+  }
+}
+
+fn render_swatch(swatch: &Swatch, depth: u32, y: &mut u32, svg: &mut String) {
+  let x = MARGIN + depth * INDENT;
+  svg.push_str(&format!(
+    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#FFFFFF\"/>\n",
+    x,
+    *y,
+    LIGHT_PANEL_X - x,
+    SWATCH_HEIGHT - 8
+  ));
+  svg.push_str(&format!(
+    "<text x=\"{}\" y=\"{}\" font-weight=\"600\"{}>{}</text>\n",
+    x + 12,
+    *y + 33,
+    if swatch.deprecated {
+      " text-decoration=\"line-through\""
+    } else {
+      ""
+    },
+    escape(&swatch.path)
+  ));
+  render_sample(&swatch.light, LIGHT_PANEL_X, "#FFFFFF", "#1C1C1E", *y, svg);
+  render_sample(&swatch.dark, DARK_PANEL_X, "#000000", "#F2F2F7", *y, svg);
+  *y += SWATCH_HEIGHT;
+}
+
+fn render_sample(
+  sample: &Sample,
+  x: u32,
+  background: &str,
+  foreground: &str,
+  y: u32,
+  svg: &mut String,
+) {
+  svg.push_str(&format!(
+    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+    x,
+    y,
+    PANEL_WIDTH,
+    SWATCH_HEIGHT - 8,
+    background
+  ));
+  svg.push_str(&format!(
+    "<rect x=\"{}\" y=\"{}\" width=\"40\" height=\"40\" rx=\"6\" fill=\"{}\" stroke=\"#7F7F7F\" stroke-opacity=\"0.4\"/>\n",
+    x + 8,
+    y + 8,
+    sample.css_color()
+  ));
+
+  let mut details = format!("{:.2}:1", sample.contrast);
+  if let Some(variable) = &sample.variable {
+    details = format!("${} · {}", variable, details);
+  }
+  svg.push_str(&format!(
+    "<text x=\"{x}\" y=\"{}\" fill=\"{foreground}\">{}</text>\n<text x=\"{x}\" y=\"{}\" font-size=\"12\" fill=\"{foreground}\" fill-opacity=\"0.7\">{}</text>\n",
+    y + 24,
+    escape(&sample.hex),
+    y + 42,
+    escape(&details),
+    x = x + 60,
+    foreground = foreground
+  ));
+}
@@ -0,0 +1,82 @@
+use parser::{ast::Document, parse_document};
+use preview::{render_preview, write_preview, Format};
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn html_preview() {
+  let html = render_preview(&test_document(), Format::Html).expect("Could not render preview");
+  assert_eq!(
+    html,
+    fs::read_to_string("tests/fixtures/preview.html").unwrap()
+  );
+}
+
+#[test]
+fn svg_preview() {
+  let svg = render_preview(&test_document(), Format::Svg).expect("Could not render preview");
+  assert_eq!(
+    svg,
+    fs::read_to_string("tests/fixtures/preview.svg").unwrap()
+  );
+}
+
+#[test]
+fn preview_is_self_contained() {
+  for format in [Format::Html, Format::Svg] {
+    let contents = render_preview(&test_document(), format).unwrap();
+    assert!(!contents.contains("<link"));
+    assert!(!contents.contains("<script"));
+    assert!(!contents.contains("url("));
+  }
+}
+
+#[test]
+fn escapes_doc_comments() {
+  let doc = parse_document(
+    r#"
+    /// Use for <b>bold</b> & "quoted" text
+    Text: #000000
+    "#
+    .to_string(),
+  )
+  .unwrap();
+  let html = render_preview(&doc, Format::Html).unwrap();
+  assert!(html.contains("Use for &lt;b&gt;bold&lt;/b&gt; &amp; &quot;quoted&quot; text"));
+}
+
+#[test]
+fn write_preview_file() {
+  let tmp_dir = TempDir::new("preview").expect("Create temp dir failed");
+  let path = tmp_dir.path().join("colors.svg");
+  write_preview(&test_document(), &path, Format::Svg).expect("Could not write preview");
+  assert_eq!(
+    fs::read_to_string(path).unwrap(),
+    fs::read_to_string("tests/fixtures/preview.svg").unwrap()
+  );
+}
+
+fn test_document() -> Document {
+  let contents = r#"
+    $white: #ffffff
+    $black: #000000
+    $grey: #777777
+    $classic: (light: $black, dark: $white)
+
+    /// Separates content on light backgrounds
+    LightContentSeparator: #000000 10%
+
+    Text {
+      Primary: $classic
+      Secondary: (light: $grey, dark: #A0A0A0)
+      @deprecated
+      Legacy: #333333
+
+      /// Text on buttons
+      Button {
+        Title: (light: $white, dark: $black)
+      }
+    }
+  "#;
+  parse_document(contents.to_string()).expect("Could not parse document")
+}
@@ -5,6 +5,22 @@ use std::path::PathBuf;
 pub enum Error {
   #[error("Contents of file {path:?} remain identical. The file has not been touched.")]
   FileIsIdentical { path: PathBuf },
+  #[error("{first} and {second} would both be generated as Swift identifier {identifier}")]
+  IdentifierCollision {
+    identifier: String,
+    first: String,
+    second: String,
+  },
+  #[error("{first} and {second} would both reference the color named {name}")]
+  AssetNameCollision {
+    name: String,
+    first: String,
+    second: String,
+  },
+  #[error("{path} is renamed to {replacement}, which is not a declaration")]
+  UnknownReplacement { path: String, replacement: String },
+  #[error("{path} is renamed to {replacement}, but declares different colors")]
+  ReplacementColorsDiffer { path: String, replacement: String },
   #[error(transparent)]
   Io {
     #[from]
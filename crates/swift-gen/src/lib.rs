@@ -1,6 +1,13 @@
 mod error;
+mod naming;
 mod renderers;
 mod swift_gen;
 
 pub use self::error::Error;
-pub use self::swift_gen::{gen_swift, AccessLevel, RenderMode};
+pub use self::naming::SwiftNaming;
+pub use self::swift_gen::{
+  gen_swift, gen_swift_in, render_swift, AccessLevel, BundleStrategy, ColorSpace, MissingColor,
+  Options, RenderMode, RootType,
+};
+pub use file_tree::{Change, FileSystem, MemoryFileSystem, OsFileSystem};
+pub use parser::AssetNaming;
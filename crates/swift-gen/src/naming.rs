@@ -0,0 +1,177 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Describes how identifiers of the stylesheet are turned into Swift identifiers.
+///
+/// Hyphens are not valid in Swift identifiers, so they always separate words (`brand-blue`
+/// becomes `brandBlue`). Underscores are kept by `AsIs` and separate words in `LowerCamelCase`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SwiftNaming {
+  /// Identifiers are used verbatim, apart from hyphens.
+  #[default]
+  AsIs,
+  /// Colors become lowerCamelCase members, rulesets UpperCamelCase types.
+  LowerCamelCase,
+}
+
+impl FromStr for SwiftNaming {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<SwiftNaming, ()> {
+    match s.to_lowercase().as_ref() {
+      "as-is" => Ok(SwiftNaming::AsIs),
+      "lower-camel-case" => Ok(SwiftNaming::LowerCamelCase),
+      _ => Err(()),
+    }
+  }
+}
+
+impl fmt::Display for SwiftNaming {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::AsIs => "as-is",
+      Self::LowerCamelCase => "lower-camel-case",
+    })
+  }
+}
+
+impl SwiftNaming {
+  /// The name of the `static let` generated for a color.
+  pub fn member_name(&self, identifier: &str) -> String {
+    let name = match self {
+      Self::AsIs => join_words(identifier, &['-'], |word| word.to_string()),
+      Self::LowerCamelCase => join_words(identifier, &['-', '_'], lower_first_word),
+    };
+    sanitize(name)
+  }
+
+  /// The name of the `enum` generated for a ruleset.
+  pub fn type_name(&self, identifier: &str) -> String {
+    let name = match self {
+      Self::AsIs => join_words(identifier, &['-'], |word| word.to_string()),
+      Self::LowerCamelCase => join_words(identifier, &['-', '_'], upper_first_char),
+    };
+    sanitize(name)
+  }
+}
+
+/// Splits `identifier` at `separators` and joins the words in camel case, transforming the first
+/// word with `first_word`.
+fn join_words(identifier: &str, separators: &[char], first_word: fn(&str) -> String) -> String {
+  let mut words = identifier
+    .split(|c| separators.contains(&c))
+    .filter(|word| !word.is_empty());
+
+  match words.next() {
+    Some(first) => std::iter::once(first_word(first))
+      .chain(words.map(upper_first_char))
+      .collect(),
+    None => identifier.replace('-', "_"),
+  }
+}
+
+/// Lowercases the leading word of `identifier`, treating a run of uppercase characters as an
+/// acronym (e.g. `URLBackground` becomes `urlBackground`).
+fn lower_first_word(identifier: &str) -> String {
+  let chars: Vec<char> = identifier.chars().collect();
+  let upper_count = chars.iter().take_while(|c| c.is_uppercase()).count();
+
+  let lower_count = match upper_count {
+    0 => 0,
+    n if n == chars.len() => n,
+    1 => 1,
+    n if chars[n].is_lowercase() => n - 1,
+    n => n,
+  };
+
+  chars
+    .iter()
+    .enumerate()
+    .flat_map(|(idx, c)| {
+      if idx < lower_count {
+        c.to_lowercase().collect::<Vec<_>>()
+      } else {
+        vec![*c]
+      }
+    })
+    .collect()
+}
+
+fn upper_first_char(identifier: &str) -> String {
+  let mut chars = identifier.chars();
+  match chars.next() {
+    Some(first) => first.to_uppercase().chain(chars).collect(),
+    None => String::new(),
+  }
+}
+
+/// Turns `name` into a valid Swift identifier by prefixing names that start with a digit or
+/// consist of underscores only with an underscore and escaping keywords with backticks.
+fn sanitize(name: String) -> String {
+  if name.starts_with(|c: char| c.is_ascii_digit()) || name.chars().all(|c| c == '_') {
+    return format!("_{}", name);
+  }
+  if SWIFT_KEYWORDS.contains(&name.as_str()) {
+    return format!("`{}`", name);
+  }
+  name
+}
+
+const SWIFT_KEYWORDS: &[&str] = &[
+  "Any",
+  "Protocol",
+  "Self",
+  "Type",
+  "as",
+  "associatedtype",
+  "break",
+  "case",
+  "catch",
+  "class",
+  "continue",
+  "default",
+  "defer",
+  "deinit",
+  "do",
+  "else",
+  "enum",
+  "extension",
+  "fallthrough",
+  "false",
+  "fileprivate",
+  "for",
+  "func",
+  "guard",
+  "if",
+  "import",
+  "in",
+  "init",
+  "inout",
+  "internal",
+  "is",
+  "let",
+  "nil",
+  "open",
+  "operator",
+  "private",
+  "precedencegroup",
+  "protocol",
+  "public",
+  "repeat",
+  "rethrows",
+  "return",
+  "self",
+  "static",
+  "struct",
+  "subscript",
+  "super",
+  "switch",
+  "throw",
+  "throws",
+  "true",
+  "try",
+  "typealias",
+  "var",
+  "where",
+  "while",
+];
@@ -1,22 +1,39 @@
+use super::super::{BundleStrategy, MissingColor};
 use super::data::*;
 use super::renderer::{Renderer, RendererConfig};
 
-pub struct ColorSetRenderer {}
+pub struct ColorSetRenderer {
+  pub bundle: BundleStrategy,
+  pub missing_color: MissingColor,
+}
 
 impl Renderer for ColorSetRenderer {
   fn render_into(&self, ruleset: &RuleSet, d: &mut String, config: &RendererConfig) {
     d.push_str(
       "// This file is automatically generated. Do not edit, your changes will be erased.\n",
     );
-    d.push_str("\n");
+    d.push('\n');
     d.push_str("import UIKit\n");
-    d.push_str("\n");
-    d.push_str("extension UIColor {\n");
-    self.render_ruleset_into(ruleset, d, config);
-    d.push_str("}\n");
-    d.push_str("\n");
-    d.push_str(
-      r#"private final class BundleToken {
+    d.push('\n');
+    config.render_root_into(ruleset, d, |d| {
+      self.render_ruleset_items_into(ruleset, d, config)
+    });
+
+    if self.missing_color == MissingColor::AssertionFailure {
+      d.push('\n');
+      d.push_str(
+        r#"private func missingColor(named name: String) -> UIColor {
+  assertionFailure("Color named \(name) is missing from the asset catalog.")
+  return .clear
+}
+"#,
+      );
+    }
+
+    if self.bundle == BundleStrategy::Token {
+      d.push('\n');
+      d.push_str(
+        r#"private final class BundleToken {
   static let bundle: Bundle = {
     #if SWIFT_PACKAGE
     return Bundle.module
@@ -26,27 +43,40 @@ impl Renderer for ColorSetRenderer {
   }()
 }
 "#,
-    );
+      );
+    }
   }
 }
 
 impl ColorSetRenderer {
   fn render_ruleset_into(&self, ruleset: &RuleSet, d: &mut String, config: &RendererConfig) {
+    config.render_doc_into(&ruleset.doc, ruleset.identifier.depth, d);
     d.push_str(&format!(
       "{}{}enum {} {{\n",
       config.indent(ruleset.identifier.depth),
-      config.access_level.to_string(),
+      config.access_level,
       ruleset.identifier.short
     ));
+    self.render_ruleset_items_into(ruleset, d, config);
+    d.push_str(&format!("{}}}\n", config.indent(ruleset.identifier.depth)));
+  }
 
+  fn render_ruleset_items_into(&self, ruleset: &RuleSet, d: &mut String, config: &RendererConfig) {
     for item in &ruleset.items {
       match item {
         RuleSetItem::Declaration(decl) => self.render_declaration_into(decl, d, config),
         RuleSetItem::RuleSet(ruleset) => self.render_ruleset_into(ruleset, d, config),
       }
     }
+  }
 
-    d.push_str(&format!("{}}}\n", config.indent(ruleset.identifier.depth)));
+  fn bundle_expression(&self) -> &str {
+    match &self.bundle {
+      BundleStrategy::Token => "BundleToken.bundle",
+      BundleStrategy::Main => "Bundle.main",
+      BundleStrategy::Module => "Bundle.module",
+      BundleStrategy::Custom(expression) => expression,
+    }
   }
 
   fn render_declaration_into(
@@ -55,12 +85,34 @@ impl ColorSetRenderer {
     d: &mut String,
     config: &RendererConfig,
   ) {
+    let name = &declaration.identifier.full;
+    let value = match declaration.replacement() {
+      Some(replacement) => replacement.expression.clone(),
+      None => {
+        let unwrap = match &self.missing_color {
+          MissingColor::ForceUnwrap => "!".to_string(),
+          MissingColor::Fallback(expression) => format!(" ?? {}", expression),
+          MissingColor::AssertionFailure => format!(" ?? missingColor(named: \"{}\")", name),
+        };
+        format!(
+          "UIColor(named: \"{}\", in: {}, compatibleWith: nil){}",
+          name,
+          self.bundle_expression(),
+          unwrap
+        )
+      }
+    };
+
+    config.render_doc_into(&declaration.doc, declaration.identifier.depth, d);
+    if let Some(deprecation) = &declaration.deprecation {
+      config.render_deprecation_into(deprecation, declaration.identifier.depth, d);
+    }
     d.push_str(&format!(
-      "{}{}static let {} = UIColor(named: \"{}\", in: BundleToken.bundle, compatibleWith: nil)!\n",
+      "{}{}static let {} = {}\n",
       config.indent(declaration.identifier.depth),
-      config.access_level.to_string(),
+      config.access_level,
       declaration.identifier.short,
-      declaration.identifier.full
+      value
     ))
   }
 }
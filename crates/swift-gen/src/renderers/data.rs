@@ -1,16 +1,24 @@
-use super::super::Error;
-use parser::ast::{
-  Color as ASTColor, Document as ASTDocument, DocumentItem as ASTDocumentItem,
-  RuleSetItem as ASTRuleSetItem, Value as ASTValue,
+use super::super::{Error, Options, RootType};
+use parser::ast::{Annotation as ASTAnnotation, Color as ASTColor, Document as ASTDocument};
+use parser::color_space::hex_string;
+use parser::resolved::{
+  Declaration as ResolvedDeclaration, Document as ResolvedDocument, Item as ResolvedItem,
+  SourcedColor, Value as ResolvedValue,
 };
-use parser::{ResolvedColorSet, ResolvedVariable, VarContext};
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
 #[derive(Debug)]
 pub struct Identifier {
+  /// The Swift identifier of the generated member or type.
   pub short: String,
+  /// The name of the color in the asset catalog.
   pub full: String,
   pub depth: usize,
+  /// The dot-separated path of the declaration in the stylesheet.
+  pub path: String,
+  /// The dot-separated path of the Swift member relative to the root namespace.
+  pub swift_path: String,
 }
 
 #[derive(Debug)]
@@ -23,6 +31,8 @@ pub enum RuleSetItem {
 pub struct RuleSet {
   pub identifier: Identifier,
   pub items: Vec<RuleSetItem>,
+  /// The lines of the doc comment of the generated type.
+  pub doc: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -35,14 +45,37 @@ pub enum DeclarationValue {
 pub struct Declaration {
   pub identifier: Identifier,
   pub value: DeclarationValue,
+  /// The lines of the doc comment of the generated member, ending with a preview of the color.
+  pub doc: Vec<String>,
+  pub deprecation: Option<Deprecation>,
 }
 
 #[derive(Debug)]
+pub struct Deprecation {
+  pub message: Option<String>,
+  pub renamed: Option<Replacement>,
+}
+
+/// The declaration a deprecated declaration has been renamed to.
+#[derive(Debug)]
+pub struct Replacement {
+  /// The dot-separated path of the replacement in the stylesheet.
+  pub path: String,
+  /// The dot-separated path of the Swift member relative to the root namespace, e.g. `Text.Body`.
+  /// Empty until the replacement has been resolved.
+  pub name: String,
+  /// A Swift expression referencing the replacement, e.g. `Custom.Text.Body`.
+  pub expression: String,
+}
+
+#[derive(Debug, Clone)]
 pub struct Color {
   pub r: u8,
   pub g: u8,
   pub b: u8,
   pub a: f32,
+  /// See `parser::ast::Color::linear`.
+  pub linear: Option<[f64; 3]>,
 }
 
 #[derive(Debug)]
@@ -57,6 +90,7 @@ impl Hash for Color {
     self.g.hash(state);
     self.b.hash(state);
     self.comparable_alpha().hash(state);
+    self.comparable_linear().hash(state);
   }
 }
 
@@ -66,6 +100,7 @@ impl PartialEq for Color {
       && self.g == other.g
       && self.b == other.b
       && self.comparable_alpha() == other.comparable_alpha()
+      && self.comparable_linear() == other.comparable_linear()
   }
 }
 
@@ -75,6 +110,103 @@ impl Color {
   fn comparable_alpha(&self) -> i32 {
     (self.a * 1000.0) as i32
   }
+
+  fn comparable_linear(&self) -> Option<[u64; 3]> {
+    self.linear.map(|linear| linear.map(f64::to_bits))
+  }
+
+  /// The color in the representation of the parser, e.g. to convert it into another color space.
+  pub fn ast_color(&self) -> ASTColor {
+    ASTColor {
+      r: self.r,
+      g: self.g,
+      b: self.b,
+      a: self.a,
+      linear: self.linear,
+    }
+  }
+
+  /// The color in the notation of the stylesheet, e.g. `#151618` or `#000000 50%`.
+  fn hex_string(&self) -> String {
+    hex_string(&self.ast_color())
+  }
+}
+
+impl DeclarationValue {
+  /// The light and dark color, which are the same for a single color.
+  fn appearances(&self) -> (&Color, &Color) {
+    match self {
+      Self::Color(color) => (color, color),
+      Self::ColorSet(colorset) => (&colorset.light, &colorset.dark),
+    }
+  }
+
+  /// Describes the resolved colors of `decl`, naming the variables they were taken from, e.g.
+  /// `light: #151618 ($grey1), dark: #E7E8EA`.
+  fn preview(&self, decl: &ResolvedDeclaration) -> String {
+    let annotated = |color: &Color, variable: Option<&String>| match variable {
+      Some(variable) => format!("{} (${})", color.hex_string(), variable),
+      None => color.hex_string(),
+    };
+    match (self, &decl.value) {
+      (Self::Color(color), _) => annotated(color, decl.variable.as_ref()),
+      (Self::ColorSet(colorset), ResolvedValue::ColorSet(sourced)) if decl.variable.is_none() => {
+        format!(
+          "light: {}, dark: {}",
+          annotated(&colorset.light, sourced.light.variable.as_ref()),
+          annotated(&colorset.dark, sourced.dark.variable.as_ref())
+        )
+      }
+      (Self::ColorSet(colorset), _) => {
+        let preview = format!(
+          "light: {}, dark: {}",
+          colorset.light.hex_string(),
+          colorset.dark.hex_string()
+        );
+        match &decl.variable {
+          Some(variable) => format!("{} (${})", preview, variable),
+          None => preview,
+        }
+      }
+    }
+  }
+}
+
+impl Declaration {
+  pub fn replacement(&self) -> Option<&Replacement> {
+    self
+      .deprecation
+      .as_ref()
+      .and_then(|deprecation| deprecation.renamed.as_ref())
+  }
+}
+
+impl Deprecation {
+  fn from_annotations(annotations: &[ASTAnnotation]) -> Option<Self> {
+    if annotations.is_empty() {
+      return None;
+    }
+
+    let mut deprecation = Deprecation {
+      message: None,
+      renamed: None,
+    };
+
+    for annotation in annotations {
+      match annotation {
+        ASTAnnotation::Deprecated { message } => deprecation.message = message.clone(),
+        ASTAnnotation::Renamed { identifier } => {
+          deprecation.renamed = Some(Replacement {
+            path: identifier.clone(),
+            name: "".to_string(),
+            expression: "".to_string(),
+          })
+        }
+      }
+    }
+
+    Some(deprecation)
+  }
 }
 
 impl RuleSetItem {
@@ -87,21 +219,94 @@ impl RuleSetItem {
 }
 
 impl RuleSet {
-  pub fn derive_from(doc: &ASTDocument) -> Result<Self, Error> {
-    let ctx = VarContext::derive_from(doc);
-    let items: Vec<ASTRuleSetItem> = doc
-      .items
-      .iter()
-      .filter_map(|item| match item {
-        ASTDocumentItem::Variable(_) => None,
-        ASTDocumentItem::Declaration(decl) => Some(ASTRuleSetItem::Declaration(decl.clone())),
-        ASTDocumentItem::RuleSet(ruleset) => Some(ASTRuleSetItem::RuleSet(ruleset.clone())),
-      })
-      .collect();
-    RuleSet::new(Identifier::new(), &items, &ctx)
+  /// Resolves `doc` into a sorted tree of declarations, named according to `options`.
+  pub fn derive_from(doc: &ASTDocument, options: &Options) -> Result<Self, Error> {
+    let doc = ResolvedDocument::derive_from(doc)?;
+    let mut root = RuleSet::new(Identifier::root(options), vec![], &doc.items, options)?;
+
+    let mut targets: HashMap<String, Target> = HashMap::new();
+    root.collect_targets(&mut targets);
+    let root_name = root.identifier.short.clone();
+    root.resolve_replacements(&targets, &root_name)?;
+
+    Ok(root)
+  }
+
+  /// Collects every declaration by its path in the stylesheet, as the target of a `@renamed`.
+  fn collect_targets(&self, targets: &mut HashMap<String, Target>) {
+    for item in &self.items {
+      match item {
+        RuleSetItem::Declaration(decl) => {
+          let (light, dark) = decl.value.appearances();
+          targets.insert(
+            decl.identifier.path.clone(),
+            Target {
+              swift_path: decl.identifier.swift_path.clone(),
+              preview: decl.doc.last().cloned().unwrap_or_default(),
+              appearances: (light.clone(), dark.clone()),
+            },
+          );
+        }
+        RuleSetItem::RuleSet(ruleset) => ruleset.collect_targets(targets),
+      }
+    }
+  }
+
+  fn resolve_replacements(
+    &mut self,
+    targets: &HashMap<String, Target>,
+    root_name: &str,
+  ) -> Result<(), Error> {
+    for item in &mut self.items {
+      match item {
+        RuleSetItem::Declaration(decl) => {
+          let path = &decl.identifier.path;
+          if let Some(replacement) = decl
+            .deprecation
+            .as_mut()
+            .and_then(|deprecation| deprecation.renamed.as_mut())
+          {
+            let target = match targets.get(&replacement.path) {
+              Some(found) if &replacement.path != path => found,
+              _ => {
+                return Err(Error::UnknownReplacement {
+                  path: path.clone(),
+                  replacement: replacement.path.clone(),
+                })
+              }
+            };
+            // The alias returns the colors of the replacement, while the asset catalog keeps the
+            // colors of the declaration. Both have to agree.
+            let (light, dark) = decl.value.appearances();
+            if (light, dark) != (&target.appearances.0, &target.appearances.1) {
+              return Err(Error::ReplacementColorsDiffer {
+                path: path.clone(),
+                replacement: replacement.path.clone(),
+              });
+            }
+            replacement.name = target.swift_path.replace('`', "");
+            replacement.expression = format!("{}.{}", root_name, target.swift_path);
+            if let Some(last) = decl.doc.last_mut() {
+              *last = target.preview.clone();
+            }
+          }
+        }
+        RuleSetItem::RuleSet(ruleset) => ruleset.resolve_replacements(targets, root_name)?,
+      }
+    }
+    Ok(())
   }
 }
 
+/// A declaration which other declarations may be renamed to.
+struct Target {
+  /// See `Identifier::swift_path`.
+  swift_path: String,
+  /// The preview of the colors ending the doc comment of the declaration.
+  preview: String,
+  appearances: (Color, Color),
+}
+
 impl From<&ASTColor> for Color {
   fn from(color: &ASTColor) -> Self {
     Color {
@@ -109,97 +314,189 @@ impl From<&ASTColor> for Color {
       g: color.g,
       b: color.b,
       a: color.a,
+      linear: color.linear,
     }
   }
 }
 
-impl From<&ResolvedColorSet> for ColorSet {
-  fn from(colorset: &ResolvedColorSet) -> Self {
-    ColorSet {
-      light: Color::from(&colorset.light),
-      dark: Color::from(&colorset.dark),
-    }
+impl From<&SourcedColor> for Color {
+  fn from(color: &SourcedColor) -> Self {
+    Color::from(&color.color)
   }
 }
 
 impl Identifier {
-  fn new() -> Self {
+  fn root(options: &Options) -> Self {
+    let depth = match options.root_type {
+      RootType::Extension(_) => 1,
+      RootType::Enum | RootType::Struct => 0,
+    };
+
     Identifier {
-      short: "Custom".to_string(),
+      short: options.root_name.clone(),
       full: "".to_string(),
-      depth: 1,
+      depth,
+      path: "".to_string(),
+      swift_path: "".to_string(),
     }
   }
 
-  fn appending(&self, identifier: &str) -> Self {
+  fn appending_member(&self, identifier: &str, options: &Options) -> Self {
+    self.appending(
+      identifier,
+      options.swift_naming.member_name(identifier),
+      options,
+    )
+  }
+
+  fn appending_type(&self, identifier: &str, options: &Options) -> Self {
+    self.appending(
+      identifier,
+      options.swift_naming.type_name(identifier),
+      options,
+    )
+  }
+
+  fn appending(&self, identifier: &str, short: String, options: &Options) -> Self {
+    let path = if self.path.is_empty() {
+      identifier.to_string()
+    } else {
+      format!("{}.{}", self.path, identifier)
+    };
+
+    let swift_path = if self.swift_path.is_empty() {
+      short.clone()
+    } else {
+      format!("{}.{}", self.swift_path, short)
+    };
+
     Identifier {
-      short: identifier.to_string(),
-      full: format!("{}{}", self.full, identifier),
+      short,
+      full: options.asset_naming.join(&self.full, identifier),
       depth: self.depth + 1,
+      path,
+      swift_path,
     }
   }
 }
 
 impl RuleSet {
-  fn new<'a>(
+  fn new(
     identifier: Identifier,
-    items: &[ASTRuleSetItem],
-    ctx: &VarContext<'a>,
+    doc: Vec<String>,
+    items: &[ResolvedItem],
+    options: &Options,
   ) -> Result<Self, Error> {
     let mut resolved_items: Vec<RuleSetItem> = vec![];
 
     for item in items {
       match item {
-        ASTRuleSetItem::Declaration(decl) => match &decl.value {
-          ASTValue::Color(color) => resolved_items.push(RuleSetItem::Declaration(Declaration {
-            identifier: identifier.appending(&decl.identifier),
-            value: DeclarationValue::Color(color.into()),
-          })),
-          ASTValue::ColorSet(colorset) => {
-            resolved_items.push(RuleSetItem::Declaration(Declaration {
-              identifier: identifier.appending(&decl.identifier),
-              value: DeclarationValue::ColorSet(
-                ctx
-                  .resolve_colorset(colorset)
-                  .map(|cs| ColorSet::from(&cs))?,
-              ),
-            }))
+        ResolvedItem::Declaration(decl) => {
+          let value = match &decl.value {
+            ResolvedValue::Color(color) => DeclarationValue::Color(color.into()),
+            ResolvedValue::ColorSet(colorset) => DeclarationValue::ColorSet(ColorSet {
+              light: Color::from(&colorset.light),
+              dark: Color::from(&colorset.dark),
+            }),
+          };
+
+          let mut doc = decl.doc.clone();
+          if !doc.is_empty() {
+            doc.push("".to_string());
           }
-          ASTValue::Variable(variable) => match ctx.resolve(&variable)? {
-            ResolvedVariable::Color(color) => {
-              resolved_items.push(RuleSetItem::Declaration(Declaration {
-                identifier: identifier.appending(&decl.identifier),
-                value: DeclarationValue::Color(Color::from(&color)),
-              }))
-            }
-            ResolvedVariable::ColorSet(colorset) => {
-              resolved_items.push(RuleSetItem::Declaration(Declaration {
-                identifier: identifier.appending(&decl.identifier),
-                value: DeclarationValue::ColorSet(ColorSet::from(&colorset)),
-              }))
-            }
-          },
-        },
-        ASTRuleSetItem::RuleSet(ruleset) => {
-          resolved_items.push(RuleSetItem::RuleSet(RuleSet::new(
-            identifier.appending(&ruleset.identifier),
-            &ruleset.items,
-            ctx,
-          )?))
+          doc.push(value.preview(decl));
+
+          let mut decl_identifier = identifier.appending_member(decl.identifier(), options);
+          // Asset names are shared with the asset catalog and must not be derived separately.
+          decl_identifier.full = match &options.asset_namespace {
+            Some(namespace) => format!("{}/{}", namespace, decl.asset_name(options.asset_naming)),
+            None => decl.asset_name(options.asset_naming),
+          };
+
+          resolved_items.push(RuleSetItem::Declaration(Declaration {
+            identifier: decl_identifier,
+            value,
+            doc,
+            deprecation: Deprecation::from_annotations(&decl.annotations),
+          }))
         }
+        ResolvedItem::RuleSet(ruleset) => resolved_items.push(RuleSetItem::RuleSet(RuleSet::new(
+          identifier.appending_type(ruleset.identifier(), options),
+          ruleset.doc.clone(),
+          &ruleset.items,
+          options,
+        )?)),
       }
     }
 
-    resolved_items.sort_by(|a, b| {
-      a.identifier()
-        .short
-        .partial_cmp(&b.identifier().short)
-        .unwrap()
-    });
+    resolved_items.sort_by(|a, b| a.identifier().path.cmp(&b.identifier().path));
+
+    let mut members: HashMap<&str, &str> = HashMap::new();
+    for item in &resolved_items {
+      let item_identifier = item.identifier();
+      if let Some(existing_path) = members.insert(&item_identifier.short, &item_identifier.path) {
+        return Err(Error::IdentifierCollision {
+          identifier: item_identifier.short.clone(),
+          first: existing_path.to_string(),
+          second: item_identifier.path.clone(),
+        });
+      }
+    }
 
     Ok(RuleSet {
       identifier,
       items: resolved_items,
+      doc,
     })
   }
 }
+
+impl RuleSet {
+  /// Returns all declarations of the tree, depth-first in the order they are rendered.
+  pub fn declarations(&self) -> Vec<&Declaration> {
+    let mut declarations = vec![];
+    for item in &self.items {
+      match item {
+        RuleSetItem::Declaration(decl) => declarations.push(decl),
+        RuleSetItem::RuleSet(ruleset) => declarations.extend(ruleset.declarations()),
+      }
+    }
+    declarations
+  }
+
+  /// Returns the identifier of the direct member with the given Swift name.
+  pub fn find_member(&self, short: &str) -> Option<&Identifier> {
+    self
+      .items
+      .iter()
+      .map(RuleSetItem::identifier)
+      .find(|identifier| identifier.short == short)
+  }
+
+  /// Returns the first two declarations that share the same asset name.
+  pub fn find_asset_name_collision(&self) -> Option<(&Identifier, &Identifier)> {
+    let mut names: HashMap<&str, &Identifier> = HashMap::new();
+    self.find_asset_name_collision_in(&mut names)
+  }
+
+  fn find_asset_name_collision_in<'a>(
+    &'a self,
+    names: &mut HashMap<&'a str, &'a Identifier>,
+  ) -> Option<(&'a Identifier, &'a Identifier)> {
+    for item in &self.items {
+      match item {
+        RuleSetItem::Declaration(decl) => {
+          if let Some(existing) = names.insert(&decl.identifier.full, &decl.identifier) {
+            return Some((existing, &decl.identifier));
+          }
+        }
+        RuleSetItem::RuleSet(ruleset) => {
+          if let Some(collision) = ruleset.find_asset_name_collision_in(names) {
+            return Some(collision);
+          }
+        }
+      }
+    }
+    None
+  }
+}
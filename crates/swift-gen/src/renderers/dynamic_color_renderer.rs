@@ -1,5 +1,7 @@
+use super::super::ColorSpace;
 use super::data::*;
 use super::renderer::{Renderer, RendererConfig};
+use parser::color_space;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -43,25 +45,25 @@ private func dynamicColor(_ colorSet: ColorSet) -> UIColor {
 }
 "#,
     );
-    d.push_str("\n");
+    d.push('\n');
     d.push_str("private let ColorSets: [ColorSet] = [\n");
 
     for colorset in &colorset_map.colorsets {
       d.push_str(&format!(
         "{}ColorSet({}, {}),\n",
         config.indent(1),
-        colorset.light.ui_color_string(),
-        colorset
-          .dark
-          .map_or("nil".to_string(), |color| color.ui_color_string())
+        colorset.light.ui_color_string(&config.color_space),
+        colorset.dark.map_or("nil".to_string(), |color| {
+          color.ui_color_string(&config.color_space)
+        })
       ))
     }
 
     d.push_str("]\n");
-    d.push_str("\n");
-    d.push_str("extension UIColor {\n");
-    self.render_ruleset_into(ruleset, d, &colorset_map, config);
-    d.push_str("}\n");
+    d.push('\n');
+    config.render_root_into(ruleset, d, |d| {
+      self.render_ruleset_items_into(ruleset, d, &colorset_map, config)
+    });
   }
 }
 
@@ -92,14 +94,23 @@ struct ColorSetMap<'a> {
 }
 
 impl Color {
-  fn ui_color_string(&self) -> String {
-    format!(
-      "UIColor(red: {:.3}, green: {:.3}, blue: {:.3}, alpha: {:.2})",
-      f32::from(self.r) / 255.0,
-      f32::from(self.g) / 255.0,
-      f32::from(self.b) / 255.0,
-      self.a
-    )
+  fn ui_color_string(&self, color_space: &ColorSpace) -> String {
+    match color_space {
+      ColorSpace::SRGB => format!(
+        "UIColor(red: {:.3}, green: {:.3}, blue: {:.3}, alpha: {:.2})",
+        f32::from(self.r) / 255.0,
+        f32::from(self.g) / 255.0,
+        f32::from(self.b) / 255.0,
+        self.a
+      ),
+      ColorSpace::DisplayP3 => {
+        let [red, green, blue] = color_space::display_p3_components(&self.ast_color());
+        format!(
+          "UIColor(displayP3Red: {:.3}, green: {:.3}, blue: {:.3}, alpha: {:.2})",
+          red, green, blue, self.a
+        )
+      }
+    }
   }
 }
 
@@ -114,7 +125,7 @@ impl<'a> ColorSetMap<'a> {
   fn register_declaration(&mut self, decl: &'a Declaration) {
     let colorset = Rc::new(CompatColorSet::from(decl));
 
-    if self.map.get(&colorset).is_some() {
+    if self.map.contains_key(&colorset) {
       return;
     }
 
@@ -136,8 +147,11 @@ impl DynamicColorRenderer {
   fn populate_colorset_map<'a>(ruleset: &'a RuleSet, map: &mut ColorSetMap<'a>) {
     for item in &ruleset.items {
       match item {
-        RuleSetItem::Declaration(decl) => map.register_declaration(&decl),
-        RuleSetItem::RuleSet(ruleset) => Self::populate_colorset_map(&ruleset, map),
+        RuleSetItem::Declaration(decl) if decl.replacement().is_none() => {
+          map.register_declaration(decl)
+        }
+        RuleSetItem::Declaration(_) => {}
+        RuleSetItem::RuleSet(ruleset) => Self::populate_colorset_map(ruleset, map),
       }
     }
   }
@@ -149,21 +163,30 @@ impl DynamicColorRenderer {
     map: &ColorSetMap,
     config: &RendererConfig,
   ) {
+    config.render_doc_into(&ruleset.doc, ruleset.identifier.depth, d);
     d.push_str(&format!(
       "{}{}enum {} {{\n",
       config.indent(ruleset.identifier.depth),
-      config.access_level.to_string(),
+      config.access_level,
       ruleset.identifier.short
     ));
+    self.render_ruleset_items_into(ruleset, d, map, config);
+    d.push_str(&format!("{}}}\n", config.indent(ruleset.identifier.depth)));
+  }
 
+  fn render_ruleset_items_into(
+    &self,
+    ruleset: &RuleSet,
+    d: &mut String,
+    map: &ColorSetMap,
+    config: &RendererConfig,
+  ) {
     for item in &ruleset.items {
       match item {
         RuleSetItem::Declaration(decl) => self.render_declaration_into(decl, d, map, config),
         RuleSetItem::RuleSet(ruleset) => self.render_ruleset_into(ruleset, d, map, config),
       }
     }
-
-    d.push_str(&format!("{}}}\n", config.indent(ruleset.identifier.depth)));
   }
 
   fn render_declaration_into(
@@ -173,12 +196,24 @@ impl DynamicColorRenderer {
     map: &ColorSetMap,
     config: &RendererConfig,
   ) {
+    let value = match declaration.replacement() {
+      Some(replacement) => replacement.expression.clone(),
+      None => format!(
+        "dynamicColor(ColorSets[{}])",
+        map.index_for_declaration(declaration)
+      ),
+    };
+
+    config.render_doc_into(&declaration.doc, declaration.identifier.depth, d);
+    if let Some(deprecation) = &declaration.deprecation {
+      config.render_deprecation_into(deprecation, declaration.identifier.depth, d);
+    }
     d.push_str(&format!(
-      "{}{}static let {} = dynamicColor(ColorSets[{}])\n",
+      "{}{}static let {} = {}\n",
       config.indent(declaration.identifier.depth),
-      config.access_level.to_string(),
+      config.access_level,
       declaration.identifier.short,
-      map.index_for_declaration(declaration)
+      value
     ))
   }
 }
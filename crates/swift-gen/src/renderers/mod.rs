@@ -5,4 +5,4 @@ mod renderer;
 
 pub use colorset_renderer::ColorSetRenderer;
 pub use dynamic_color_renderer::DynamicColorRenderer;
-pub use renderer::{Renderer, RendererConfig};
+pub use renderer::{Renderer, RendererConfig, REGISTRY_NAME};
@@ -1,17 +1,115 @@
-use super::super::AccessLevel;
-use super::data::RuleSet;
+use super::super::{AccessLevel, ColorSpace, RootType};
+use super::data::{Deprecation, RuleSet};
+
+/// The name of the member listing all colors, see `RendererConfig::registry`.
+pub const REGISTRY_NAME: &str = "all";
 
 pub struct RendererConfig {
   tab: String,
   pub access_level: AccessLevel,
+  pub root_type: RootType,
+  pub registry: bool,
+  pub color_space: ColorSpace,
 }
 
 impl RendererConfig {
-  pub fn new(tab: &str, access_level: AccessLevel) -> Self {
+  pub fn new(
+    tab: &str,
+    access_level: AccessLevel,
+    root_type: RootType,
+    registry: bool,
+    color_space: ColorSpace,
+  ) -> Self {
     RendererConfig {
       tab: tab.to_owned(),
       access_level,
+      root_type,
+      registry,
+      color_space,
+    }
+  }
+
+  /// Renders the declaration of the root namespace around the output of `render_contents`.
+  pub fn render_root_into(
+    &self,
+    ruleset: &RuleSet,
+    d: &mut String,
+    render_contents: impl FnOnce(&mut String),
+  ) {
+    let keyword = match self.root_type {
+      RootType::Struct => "struct",
+      RootType::Extension(_) | RootType::Enum => "enum",
+    };
+
+    if let RootType::Extension(host) = &self.root_type {
+      d.push_str(&format!("extension {} {{\n", host));
     }
+    d.push_str(&format!(
+      "{}{}{} {} {{\n",
+      self.indent(ruleset.identifier.depth),
+      self.access_level,
+      keyword,
+      ruleset.identifier.short
+    ));
+    render_contents(d);
+    if self.registry {
+      self.render_registry_into(ruleset, d);
+    }
+    d.push_str(&format!("{}}}\n", self.indent(ruleset.identifier.depth)));
+    if let RootType::Extension(_) = self.root_type {
+      d.push_str("}\n");
+    }
+  }
+
+  /// Renders a list of all colors that are not deprecated, named by their path in the stylesheet.
+  fn render_registry_into(&self, ruleset: &RuleSet, d: &mut String) {
+    let depth = ruleset.identifier.depth + 1;
+
+    d.push_str(&format!(
+      "{}{}static let {}: [(name: String, color: UIColor)] = [\n",
+      self.indent(depth),
+      self.access_level,
+      REGISTRY_NAME
+    ));
+    for declaration in ruleset.declarations() {
+      if declaration.deprecation.is_none() {
+        d.push_str(&format!(
+          "{}(\"{}\", {}),\n",
+          self.indent(depth + 1),
+          declaration.identifier.path,
+          declaration.identifier.swift_path
+        ));
+      }
+    }
+    d.push_str(&format!("{}]\n", self.indent(depth)));
+  }
+
+  /// Renders `doc` as a Swift doc comment at the given depth.
+  pub fn render_doc_into(&self, doc: &[String], depth: usize, d: &mut String) {
+    for line in doc {
+      if line.is_empty() {
+        d.push_str(&format!("{}///\n", self.indent(depth)));
+      } else {
+        d.push_str(&format!("{}/// {}\n", self.indent(depth), line));
+      }
+    }
+  }
+
+  /// Renders an `@available` attribute marking a member as deprecated.
+  pub fn render_deprecation_into(&self, deprecation: &Deprecation, depth: usize, d: &mut String) {
+    let mut arguments = vec!["*".to_string(), "deprecated".to_string()];
+    if let Some(replacement) = &deprecation.renamed {
+      arguments.push(format!("renamed: \"{}\"", replacement.name));
+    }
+    if let Some(message) = &deprecation.message {
+      arguments.push(format!("message: \"{}\"", message.replace('\\', "\\\\")));
+    }
+
+    d.push_str(&format!(
+      "{}@available({})\n",
+      self.indent(depth),
+      arguments.join(", ")
+    ));
   }
 
   pub fn indent(&self, depth: usize) -> String {
@@ -1,16 +1,18 @@
 use super::renderers::{
   data::RuleSet as RendererRuleSet, ColorSetRenderer, DynamicColorRenderer, Renderer,
-  RendererConfig,
+  RendererConfig, REGISTRY_NAME,
 };
-use super::Error;
+use super::{Error, SwiftNaming};
+use file_tree::{Change, FileSystem, OsFileSystem};
 use parser::ast::Document;
-use std::fs;
-use std::io::prelude::Read;
+use parser::AssetNaming;
+use std::fmt;
 use std::path::Path;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum RenderMode {
+  #[default]
   ColorSet,
   DynamicColor,
 }
@@ -27,8 +29,9 @@ impl FromStr for RenderMode {
   }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum AccessLevel {
+  #[default]
   Internal,
   Public,
 }
@@ -45,48 +48,205 @@ impl FromStr for AccessLevel {
   }
 }
 
-impl ToString for AccessLevel {
-  fn to_string(&self) -> String {
-    match self {
-      AccessLevel::Internal => "".to_string(),
-      AccessLevel::Public => "public ".to_string(),
+impl fmt::Display for AccessLevel {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      AccessLevel::Internal => "",
+      AccessLevel::Public => "public ",
+    })
+  }
+}
+
+/// Describes the color space of the colors created in `RenderMode::DynamicColor`. The colors of the
+/// stylesheet are sRGB and are converted into the components of the color space.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ColorSpace {
+  #[default]
+  SRGB,
+  DisplayP3,
+}
+
+impl FromStr for ColorSpace {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<ColorSpace, ()> {
+    match s.to_lowercase().as_ref() {
+      "srgb" => Ok(ColorSpace::SRGB),
+      "display-p3" => Ok(ColorSpace::DisplayP3),
+      _ => Err(()),
     }
   }
 }
 
-pub fn gen_swift(
-  doc: &Document,
-  path: impl AsRef<Path>,
-  mode: RenderMode,
-  force_overwrite: bool,
-  access_level: AccessLevel,
-) -> Result<(), Error> {
-  let root = RendererRuleSet::derive_from(doc)?;
+/// Describes how the root namespace of the generated code is declared.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RootType {
+  /// A nested enum in an extension of the given type, e.g. `extension UIColor { enum Custom }`.
+  Extension(String),
+  /// A standalone top-level `enum`.
+  Enum,
+  /// A standalone top-level `struct`.
+  Struct,
+}
+
+impl Default for RootType {
+  fn default() -> Self {
+    RootType::Extension("UIColor".to_string())
+  }
+}
+
+/// Describes which bundle colors are loaded from in `RenderMode::ColorSet`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum BundleStrategy {
+  /// A private `BundleToken` class resolving to `Bundle.module` in Swift packages and to the
+  /// bundle containing the class otherwise.
+  #[default]
+  Token,
+  /// `Bundle.main`, e.g. for app targets.
+  Main,
+  /// `Bundle.module`, the resource bundle of a Swift package.
+  Module,
+  /// A Swift expression evaluating to a `Bundle`, e.g. `ResourceBundle.colors`.
+  Custom(String),
+}
+
+/// Describes what happens in `RenderMode::ColorSet` if a color is missing from the asset catalog.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum MissingColor {
+  /// Force-unwrap the color, crashing at runtime.
+  #[default]
+  ForceUnwrap,
+  /// Use the given Swift expression instead, e.g. `.magenta`.
+  Fallback(String),
+  /// Trigger an `assertionFailure` in debug builds and use `.clear` otherwise.
+  AssertionFailure,
+}
+
+/// Describes what the generated Swift code looks like.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+  pub mode: RenderMode,
+  pub access_level: AccessLevel,
+  /// Must match the naming the asset catalog was written with, so that the lookups generated in
+  /// `RenderMode::ColorSet` reference the correct colors.
+  pub asset_naming: AssetNaming,
+  /// The name of the group folder the asset catalog has been written into with
+  /// `provides_namespace`, e.g. `GeneratedColors`. Prefixes the names of the colors looked up in
+  /// `RenderMode::ColorSet`.
+  pub asset_namespace: Option<String>,
+  pub swift_naming: SwiftNaming,
+  /// The name of the type all colors are nested in, e.g. `Custom` for `UIColor.Custom`.
+  pub root_name: String,
+  pub root_type: RootType,
+  pub bundle: BundleStrategy,
+  pub missing_color: MissingColor,
+  /// Adds `static let all: [(name: String, color: UIColor)]` to the root namespace, listing every
+  /// color that is not deprecated.
+  pub registry: bool,
+  /// The color space of the colors created in `RenderMode::DynamicColor`. Ignored in
+  /// `RenderMode::ColorSet`, where the asset catalog determines the color space.
+  pub color_space: ColorSpace,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Options {
+      mode: RenderMode::default(),
+      access_level: AccessLevel::default(),
+      asset_naming: AssetNaming::default(),
+      asset_namespace: None,
+      swift_naming: SwiftNaming::default(),
+      root_name: "Custom".to_string(),
+      root_type: RootType::default(),
+      bundle: BundleStrategy::default(),
+      missing_color: MissingColor::default(),
+      registry: false,
+      color_space: ColorSpace::default(),
+    }
+  }
+}
+
+/// Renders `doc` into Swift code without touching the file system.
+pub fn render_swift(doc: &Document, options: &Options) -> Result<String, Error> {
+  let root = RendererRuleSet::derive_from(doc, options)?;
+
+  if options.mode == RenderMode::ColorSet {
+    if let Some((first, second)) = root.find_asset_name_collision() {
+      return Err(Error::AssetNameCollision {
+        name: second.full.clone(),
+        first: first.path.clone(),
+        second: second.path.clone(),
+      });
+    }
+  }
+
+  if options.registry {
+    if let Some(identifier) = root.find_member(REGISTRY_NAME) {
+      return Err(Error::IdentifierCollision {
+        identifier: REGISTRY_NAME.to_string(),
+        first: identifier.path.clone(),
+        second: "the registry of all colors".to_string(),
+      });
+    }
+  }
 
   let mut contents = String::new();
-  let config = RendererConfig::new("  ", access_level);
+  let config = RendererConfig::new(
+    "  ",
+    options.access_level.clone(),
+    options.root_type.clone(),
+    options.registry,
+    options.color_space.clone(),
+  );
 
-  let renderer: Box<dyn Renderer> = match mode {
-    RenderMode::ColorSet => Box::new(ColorSetRenderer {}),
+  let renderer: Box<dyn Renderer> = match options.mode {
+    RenderMode::ColorSet => Box::new(ColorSetRenderer {
+      bundle: options.bundle.clone(),
+      missing_color: options.missing_color.clone(),
+    }),
     RenderMode::DynamicColor => Box::new(DynamicColorRenderer {}),
   };
   renderer.render_into(&root, &mut contents, &config);
 
-  let data = contents.as_bytes();
+  Ok(contents)
+}
 
-  if !force_overwrite && path.as_ref().exists() {
-    let mut existing_data = Vec::new();
-    let mut existing_file = fs::File::open(&path)?;
-    existing_file.read_to_end(&mut existing_data)?;
+/// Renders `doc` into a Swift file at `path`.
+///
+/// The file is replaced atomically and only after rendering succeeded. Returns the change made to
+/// the file. If `dry_run` is set, the change is only computed and nothing is written.
+pub fn gen_swift(
+  doc: &Document,
+  path: impl AsRef<Path>,
+  options: &Options,
+  force_overwrite: bool,
+  dry_run: bool,
+) -> Result<Vec<Change>, Error> {
+  gen_swift_in(&OsFileSystem, doc, path, options, force_overwrite, dry_run)
+}
 
-    if existing_data == data {
-      return Err(Error::FileIsIdentical {
-        path: path.as_ref().into(),
-      });
-    }
+/// Like `gen_swift`, but reads and writes the file in `fs`.
+pub fn gen_swift_in(
+  fs: &dyn FileSystem,
+  doc: &Document,
+  path: impl AsRef<Path>,
+  options: &Options,
+  force_overwrite: bool,
+  dry_run: bool,
+) -> Result<Vec<Change>, Error> {
+  let path = path.as_ref();
+  let contents = render_swift(doc, options)?;
+  let data = contents.as_bytes();
+  let change = Change::for_file_in(fs, path, data)?;
+
+  if !force_overwrite && change.is_none() {
+    return Err(Error::FileIsIdentical { path: path.into() });
   }
 
-  fs::write(path, data)?;
+  if !dry_run {
+    fs.write_file(path, data)?;
+  }
 
-  Ok(())
+  Ok(change.into_iter().collect())
 }
@@ -0,0 +1,43 @@
+// This file is automatically generated. Do not edit, your changes will be erased.
+
+import UIKit
+
+extension UIColor {
+  enum Custom {
+    /// light: #F1F2F2, dark: #222525
+    static let LightContentSeparator = UIColor(named: "LightContentSeparator", in: Bundle.module, compatibleWith: nil)!
+    enum NumericInput {
+      /// light: #D6D9DE 30%, dark: #313131 40%
+      static let Background = UIColor(named: "NumericInputBackground", in: Bundle.module, compatibleWith: nil)!
+      enum DoneKey {
+        /// light: #19C8FF ($mediumBright), dark: #5753CF ($brightAccent)
+        static let Background = UIColor(named: "NumericInputDoneKeyBackground", in: Bundle.module, compatibleWith: nil)!
+        /// light: #70D1FA ($mediumBrightHighlight), dark: #677ADB
+        static let Highlight = UIColor(named: "NumericInputDoneKeyHighlight", in: Bundle.module, compatibleWith: nil)!
+        /// #6E7073
+        static let Shadow = UIColor(named: "NumericInputDoneKeyShadow", in: Bundle.module, compatibleWith: nil)!
+        /// light: #000000, dark: #FFFFFF ($classic)
+        static let Text = UIColor(named: "NumericInputDoneKeyText", in: Bundle.module, compatibleWith: nil)!
+      }
+      enum NumericKey {
+        /// light: #FFFFFF ($white), dark: #434343
+        static let Background = UIColor(named: "NumericInputNumericKeyBackground", in: Bundle.module, compatibleWith: nil)!
+        /// light: #C4CCDA, dark: #666666
+        static let Highlight = UIColor(named: "NumericInputNumericKeyHighlight", in: Bundle.module, compatibleWith: nil)!
+        /// light: #848587, dark: #000000 ($black)
+        static let Shadow = UIColor(named: "NumericInputNumericKeyShadow", in: Bundle.module, compatibleWith: nil)!
+        /// light: #000000, dark: #FFFFFF ($classic)
+        static let Text = UIColor(named: "NumericInputNumericKeyText", in: Bundle.module, compatibleWith: nil)!
+      }
+    }
+    /// Colors for text and glyphs.
+    enum Text {
+      /// light: #151618, dark: #E7E8EA
+      static let Primary = UIColor(named: "TextPrimary", in: Bundle.module, compatibleWith: nil)!
+      /// Used for captions and placeholders.
+      ///
+      /// light: #000000 ($grey1), dark: #85868A
+      static let Secondary = UIColor(named: "TextSecondary", in: Bundle.module, compatibleWith: nil)!
+    }
+  }
+}
@@ -0,0 +1,53 @@
+// This file is automatically generated. Do not edit, your changes will be erased.
+
+import UIKit
+
+extension UIColor {
+  enum Custom {
+    /// light: #F1F2F2, dark: #222525
+    static let LightContentSeparator = UIColor(named: "LightContentSeparator", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+    enum NumericInput {
+      /// light: #D6D9DE 30%, dark: #313131 40%
+      static let Background = UIColor(named: "NumericInputBackground", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+      enum DoneKey {
+        /// light: #19C8FF ($mediumBright), dark: #5753CF ($brightAccent)
+        static let Background = UIColor(named: "NumericInputDoneKeyBackground", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+        /// light: #70D1FA ($mediumBrightHighlight), dark: #677ADB
+        static let Highlight = UIColor(named: "NumericInputDoneKeyHighlight", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+        /// #6E7073
+        static let Shadow = UIColor(named: "NumericInputDoneKeyShadow", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+        /// light: #000000, dark: #FFFFFF ($classic)
+        static let Text = UIColor(named: "NumericInputDoneKeyText", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+      }
+      enum NumericKey {
+        /// light: #FFFFFF ($white), dark: #434343
+        static let Background = UIColor(named: "NumericInputNumericKeyBackground", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+        /// light: #C4CCDA, dark: #666666
+        static let Highlight = UIColor(named: "NumericInputNumericKeyHighlight", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+        /// light: #848587, dark: #000000 ($black)
+        static let Shadow = UIColor(named: "NumericInputNumericKeyShadow", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+        /// light: #000000, dark: #FFFFFF ($classic)
+        static let Text = UIColor(named: "NumericInputNumericKeyText", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+      }
+    }
+    /// Colors for text and glyphs.
+    enum Text {
+      /// light: #151618, dark: #E7E8EA
+      static let Primary = UIColor(named: "TextPrimary", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+      /// Used for captions and placeholders.
+      ///
+      /// light: #000000 ($grey1), dark: #85868A
+      static let Secondary = UIColor(named: "TextSecondary", in: BundleToken.bundle, compatibleWith: nil) ?? .magenta
+    }
+  }
+}
+
+private final class BundleToken {
+  static let bundle: Bundle = {
+    #if SWIFT_PACKAGE
+    return Bundle.module
+    #else
+    return Bundle(for: BundleToken.self)
+    #endif
+  }()
+}
@@ -0,0 +1,53 @@
+// This file is automatically generated. Do not edit, your changes will be erased.
+
+import UIKit
+
+extension UIColor {
+  enum Custom {
+    /// light: #F1F2F2, dark: #222525
+    static let LightContentSeparator = UIColor(named: "LightContentSeparator", in: BundleToken.bundle, compatibleWith: nil)!
+    enum NumericInput {
+      /// light: #D6D9DE 30%, dark: #313131 40%
+      static let Background = UIColor(named: "NumericInput/Background", in: BundleToken.bundle, compatibleWith: nil)!
+      enum DoneKey {
+        /// light: #19C8FF ($mediumBright), dark: #5753CF ($brightAccent)
+        static let Background = UIColor(named: "NumericInput/DoneKey/Background", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #70D1FA ($mediumBrightHighlight), dark: #677ADB
+        static let Highlight = UIColor(named: "NumericInput/DoneKey/Highlight", in: BundleToken.bundle, compatibleWith: nil)!
+        /// #6E7073
+        static let Shadow = UIColor(named: "NumericInput/DoneKey/Shadow", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #000000, dark: #FFFFFF ($classic)
+        static let Text = UIColor(named: "NumericInput/DoneKey/Text", in: BundleToken.bundle, compatibleWith: nil)!
+      }
+      enum NumericKey {
+        /// light: #FFFFFF ($white), dark: #434343
+        static let Background = UIColor(named: "NumericInput/NumericKey/Background", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #C4CCDA, dark: #666666
+        static let Highlight = UIColor(named: "NumericInput/NumericKey/Highlight", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #848587, dark: #000000 ($black)
+        static let Shadow = UIColor(named: "NumericInput/NumericKey/Shadow", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #000000, dark: #FFFFFF ($classic)
+        static let Text = UIColor(named: "NumericInput/NumericKey/Text", in: BundleToken.bundle, compatibleWith: nil)!
+      }
+    }
+    /// Colors for text and glyphs.
+    enum Text {
+      /// light: #151618, dark: #E7E8EA
+      static let Primary = UIColor(named: "Text/Primary", in: BundleToken.bundle, compatibleWith: nil)!
+      /// Used for captions and placeholders.
+      ///
+      /// light: #000000 ($grey1), dark: #85868A
+      static let Secondary = UIColor(named: "Text/Secondary", in: BundleToken.bundle, compatibleWith: nil)!
+    }
+  }
+}
+
+private final class BundleToken {
+  static let bundle: Bundle = {
+    #if SWIFT_PACKAGE
+    return Bundle.module
+    #else
+    return Bundle(for: BundleToken.self)
+    #endif
+  }()
+}
@@ -4,24 +4,39 @@ import UIKit
 
 extension UIColor {
   public enum Custom {
+    /// light: #F1F2F2, dark: #222525
     public static let LightContentSeparator = UIColor(named: "LightContentSeparator", in: BundleToken.bundle, compatibleWith: nil)!
     public enum NumericInput {
+      /// light: #D6D9DE 30%, dark: #313131 40%
       public static let Background = UIColor(named: "NumericInputBackground", in: BundleToken.bundle, compatibleWith: nil)!
       public enum DoneKey {
+        /// light: #19C8FF ($mediumBright), dark: #5753CF ($brightAccent)
         public static let Background = UIColor(named: "NumericInputDoneKeyBackground", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #70D1FA ($mediumBrightHighlight), dark: #677ADB
         public static let Highlight = UIColor(named: "NumericInputDoneKeyHighlight", in: BundleToken.bundle, compatibleWith: nil)!
+        /// #6E7073
         public static let Shadow = UIColor(named: "NumericInputDoneKeyShadow", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #000000, dark: #FFFFFF ($classic)
         public static let Text = UIColor(named: "NumericInputDoneKeyText", in: BundleToken.bundle, compatibleWith: nil)!
       }
       public enum NumericKey {
+        /// light: #FFFFFF ($white), dark: #434343
         public static let Background = UIColor(named: "NumericInputNumericKeyBackground", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #C4CCDA, dark: #666666
         public static let Highlight = UIColor(named: "NumericInputNumericKeyHighlight", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #848587, dark: #000000 ($black)
         public static let Shadow = UIColor(named: "NumericInputNumericKeyShadow", in: BundleToken.bundle, compatibleWith: nil)!
+        /// light: #000000, dark: #FFFFFF ($classic)
         public static let Text = UIColor(named: "NumericInputNumericKeyText", in: BundleToken.bundle, compatibleWith: nil)!
       }
     }
+    /// Colors for text and glyphs.
     public enum Text {
+      /// light: #151618, dark: #E7E8EA
       public static let Primary = UIColor(named: "TextPrimary", in: BundleToken.bundle, compatibleWith: nil)!
+      /// Used for captions and placeholders.
+      ///
+      /// light: #000000 ($grey1), dark: #85868A
       public static let Secondary = UIColor(named: "TextSecondary", in: BundleToken.bundle, compatibleWith: nil)!
     }
   }
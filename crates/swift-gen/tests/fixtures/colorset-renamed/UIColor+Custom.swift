@@ -0,0 +1,27 @@
+// This file is automatically generated. Do not edit, your changes will be erased.
+
+import UIKit
+
+extension UIColor {
+  enum Custom {
+    enum Text {
+      /// The old name of the primary text color
+      ///
+      /// light: #151618, dark: #E7E8EA ($ink)
+      @available(*, deprecated, renamed: "Text.Primary")
+      static let Old = Custom.Text.Primary
+      /// light: #151618, dark: #E7E8EA ($ink)
+      static let Primary = UIColor(named: "TextPrimary", in: BundleToken.bundle, compatibleWith: nil)!
+    }
+  }
+}
+
+private final class BundleToken {
+  static let bundle: Bundle = {
+    #if SWIFT_PACKAGE
+    return Bundle.module
+    #else
+    return Bundle(for: BundleToken.self)
+    #endif
+  }()
+}
@@ -45,24 +45,39 @@ private let ColorSets: [ColorSet] = [
 
 extension UIColor {
   public enum Custom {
+    /// light: #F1F2F2, dark: #222525
     public static let LightContentSeparator = dynamicColor(ColorSets[0])
     public enum NumericInput {
+      /// light: #D6D9DE 30%, dark: #313131 40%
       public static let Background = dynamicColor(ColorSets[1])
       public enum DoneKey {
+        /// light: #19C8FF ($mediumBright), dark: #5753CF ($brightAccent)
         public static let Background = dynamicColor(ColorSets[2])
+        /// light: #70D1FA ($mediumBrightHighlight), dark: #677ADB
         public static let Highlight = dynamicColor(ColorSets[3])
+        /// #6E7073
         public static let Shadow = dynamicColor(ColorSets[4])
+        /// light: #000000, dark: #FFFFFF ($classic)
         public static let Text = dynamicColor(ColorSets[5])
       }
       public enum NumericKey {
+        /// light: #FFFFFF ($white), dark: #434343
         public static let Background = dynamicColor(ColorSets[6])
+        /// light: #C4CCDA, dark: #666666
         public static let Highlight = dynamicColor(ColorSets[7])
+        /// light: #848587, dark: #000000 ($black)
         public static let Shadow = dynamicColor(ColorSets[8])
+        /// light: #000000, dark: #FFFFFF ($classic)
         public static let Text = dynamicColor(ColorSets[5])
       }
     }
+    /// Colors for text and glyphs.
     public enum Text {
+      /// light: #151618, dark: #E7E8EA
       public static let Primary = dynamicColor(ColorSets[9])
+      /// Used for captions and placeholders.
+      ///
+      /// light: #000000 ($grey1), dark: #85868A
       public static let Secondary = dynamicColor(ColorSets[10])
     }
   }
@@ -0,0 +1,82 @@
+// This file is automatically generated. Do not edit, your changes will be erased.
+
+import UIKit
+
+private struct ColorSet {
+  var light: UIColor
+  var dark: UIColor?
+
+  init(_ light: UIColor, _ dark: UIColor?) {
+    self.light = light
+    self.dark = dark
+  }
+}
+
+private func dynamicColor(_ colorSet: ColorSet) -> UIColor {
+  if #available(iOS 13.0, *) {
+    return UIColor { traits -> UIColor in
+      switch traits.userInterfaceStyle {
+        case .dark:
+          return colorSet.dark ?? colorSet.light
+        case .light, .unspecified:
+          fallthrough
+        @unknown default:
+          return colorSet.light
+      }
+    }
+  } else {
+    return colorSet.light
+  }
+}
+
+private let ColorSets: [ColorSet] = [
+  ColorSet(UIColor(red: 0.945, green: 0.949, blue: 0.949, alpha: 1.00), UIColor(red: 0.133, green: 0.145, blue: 0.145, alpha: 1.00)),
+  ColorSet(UIColor(red: 0.839, green: 0.851, blue: 0.871, alpha: 0.30), UIColor(red: 0.192, green: 0.192, blue: 0.192, alpha: 0.40)),
+  ColorSet(UIColor(red: 0.098, green: 0.784, blue: 1.000, alpha: 1.00), UIColor(red: 0.341, green: 0.325, blue: 0.812, alpha: 1.00)),
+  ColorSet(UIColor(red: 0.439, green: 0.820, blue: 0.980, alpha: 1.00), UIColor(red: 0.404, green: 0.478, blue: 0.859, alpha: 1.00)),
+  ColorSet(UIColor(red: 0.431, green: 0.439, blue: 0.451, alpha: 1.00), nil),
+  ColorSet(UIColor(red: 0.000, green: 0.000, blue: 0.000, alpha: 1.00), UIColor(red: 1.000, green: 1.000, blue: 1.000, alpha: 1.00)),
+  ColorSet(UIColor(red: 1.000, green: 1.000, blue: 1.000, alpha: 1.00), UIColor(red: 0.263, green: 0.263, blue: 0.263, alpha: 1.00)),
+  ColorSet(UIColor(red: 0.769, green: 0.800, blue: 0.855, alpha: 1.00), UIColor(red: 0.400, green: 0.400, blue: 0.400, alpha: 1.00)),
+  ColorSet(UIColor(red: 0.518, green: 0.522, blue: 0.529, alpha: 1.00), UIColor(red: 0.000, green: 0.000, blue: 0.000, alpha: 1.00)),
+  ColorSet(UIColor(red: 0.082, green: 0.086, blue: 0.094, alpha: 1.00), UIColor(red: 0.906, green: 0.910, blue: 0.918, alpha: 1.00)),
+  ColorSet(UIColor(red: 0.000, green: 0.000, blue: 0.000, alpha: 1.00), UIColor(red: 0.522, green: 0.525, blue: 0.541, alpha: 1.00)),
+]
+
+enum Palette {
+  /// light: #F1F2F2, dark: #222525
+  static let LightContentSeparator = dynamicColor(ColorSets[0])
+  enum NumericInput {
+    /// light: #D6D9DE 30%, dark: #313131 40%
+    static let Background = dynamicColor(ColorSets[1])
+    enum DoneKey {
+      /// light: #19C8FF ($mediumBright), dark: #5753CF ($brightAccent)
+      static let Background = dynamicColor(ColorSets[2])
+      /// light: #70D1FA ($mediumBrightHighlight), dark: #677ADB
+      static let Highlight = dynamicColor(ColorSets[3])
+      /// #6E7073
+      static let Shadow = dynamicColor(ColorSets[4])
+      /// light: #000000, dark: #FFFFFF ($classic)
+      static let Text = dynamicColor(ColorSets[5])
+    }
+    enum NumericKey {
+      /// light: #FFFFFF ($white), dark: #434343
+      static let Background = dynamicColor(ColorSets[6])
+      /// light: #C4CCDA, dark: #666666
+      static let Highlight = dynamicColor(ColorSets[7])
+      /// light: #848587, dark: #000000 ($black)
+      static let Shadow = dynamicColor(ColorSets[8])
+      /// light: #000000, dark: #FFFFFF ($classic)
+      static let Text = dynamicColor(ColorSets[5])
+    }
+  }
+  /// Colors for text and glyphs.
+  enum Text {
+    /// light: #151618, dark: #E7E8EA
+    static let Primary = dynamicColor(ColorSets[9])
+    /// Used for captions and placeholders.
+    ///
+    /// light: #000000 ($grey1), dark: #85868A
+    static let Secondary = dynamicColor(ColorSets[10])
+  }
+}
@@ -2,7 +2,10 @@ use parser::{ast::Document, parse_document};
 use std::fs;
 use std::path::Path;
 use std::process::Command;
-use swift_gen::{gen_swift, AccessLevel, Error, RenderMode};
+use swift_gen::{
+  gen_swift, gen_swift_in, render_swift, AccessLevel, AssetNaming, BundleStrategy, Change,
+  ColorSpace, Error, MemoryFileSystem, MissingColor, Options, RenderMode, RootType, SwiftNaming,
+};
 use tempdir::TempDir;
 
 #[test]
@@ -11,13 +14,64 @@ fn generate_swift_colorset_file() {
 
   gen_swift(
     &test_document(),
-    &tmp_dir.path().join("UIColor+Custom.swift"),
-    RenderMode::ColorSet,
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options::default(),
     true,
-    AccessLevel::Internal,
+    false,
   )
   .expect("Could not write Swift file");
-  assert!(!dir_diff::is_different(&tmp_dir.path(), "tests/fixtures/colorset").unwrap());
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset").unwrap());
+}
+
+#[test]
+fn render_swift_in_memory() {
+  let contents =
+    render_swift(&test_document(), &Options::default()).expect("Could not render Swift file");
+  assert_eq!(
+    contents,
+    fs::read_to_string("tests/fixtures/colorset/UIColor+Custom.swift").unwrap()
+  );
+}
+
+#[test]
+fn generate_swift_file_into_memory_file_system() {
+  let fs = MemoryFileSystem::new();
+  let options = Options::default();
+
+  let changes = gen_swift_in(&fs, &test_document(), "Colors.swift", &options, false, true)
+    .expect("Could not render Swift file");
+  assert!(matches!(changes.as_slice(), [Change::Created { .. }]));
+  assert!(fs.paths().is_empty());
+
+  gen_swift_in(
+    &fs,
+    &test_document(),
+    "Colors.swift",
+    &options,
+    false,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert_eq!(
+    fs.get("Colors.swift"),
+    Some(
+      render_swift(&test_document(), &options)
+        .unwrap()
+        .into_bytes()
+    )
+  );
+
+  match gen_swift_in(
+    &fs,
+    &test_document(),
+    "Colors.swift",
+    &options,
+    false,
+    false,
+  ) {
+    Err(Error::FileIsIdentical { .. }) => {}
+    other => panic!("Expected FileIsIdentical error, got {:?}", other),
+  }
 }
 
 #[test]
@@ -26,14 +80,131 @@ fn generate_swift_colorset_file_with_public_access() {
 
   gen_swift(
     &test_document(),
-    &tmp_dir.path().join("UIColor+Custom.swift"),
-    RenderMode::ColorSet,
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      access_level: AccessLevel::Public,
+      ..Default::default()
+    },
     true,
-    AccessLevel::Public,
+    false,
   )
   .expect("Could not write Swift file");
   println!("{}", tmp_dir.path().to_str().unwrap());
-  assert!(!dir_diff::is_different(&tmp_dir.path(), "tests/fixtures/colorset-public").unwrap());
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-public").unwrap());
+}
+
+#[test]
+fn generate_swift_colorset_file_with_main_bundle() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      bundle: BundleStrategy::Main,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-bundle-main").unwrap());
+}
+
+#[test]
+fn generate_swift_colorset_file_with_module_bundle() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      bundle: BundleStrategy::Module,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(
+    !dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-bundle-module").unwrap()
+  );
+}
+
+#[test]
+fn generate_swift_colorset_file_with_custom_bundle() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      bundle: BundleStrategy::Custom("ResourceBundle.colors".to_string()),
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(
+    !dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-bundle-custom").unwrap()
+  );
+}
+
+#[test]
+fn generate_swift_colorset_file_with_fallback_color() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      missing_color: MissingColor::Fallback(".magenta".to_string()),
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-fallback").unwrap());
+}
+
+#[test]
+fn generate_swift_colorset_file_with_assertion_failure() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      missing_color: MissingColor::AssertionFailure,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(
+    !dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-assertion-failure").unwrap()
+  );
+}
+
+#[test]
+fn generate_swift_colorset_file_with_registry() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      registry: true,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-registry").unwrap());
 }
 
 #[test]
@@ -42,13 +213,16 @@ fn generate_swift_dynamic_color_file() {
 
   gen_swift(
     &test_document(),
-    &tmp_dir.path().join("UIColor+Custom.swift"),
-    RenderMode::DynamicColor,
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      mode: RenderMode::DynamicColor,
+      ..Default::default()
+    },
     true,
-    AccessLevel::Internal,
+    false,
   )
   .expect("Could not write Swift file");
-  assert!(!dir_diff::is_different(&tmp_dir.path(), "tests/fixtures/dynamic_color").unwrap());
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/dynamic_color").unwrap());
 }
 
 #[test]
@@ -57,13 +231,397 @@ fn generate_swift_dynamic_color_file_with_public_access() {
 
   gen_swift(
     &test_document(),
-    &tmp_dir.path().join("UIColor+Custom.swift"),
-    RenderMode::DynamicColor,
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      mode: RenderMode::DynamicColor,
+      access_level: AccessLevel::Public,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/dynamic_color-public").unwrap());
+}
+
+#[test]
+fn generate_swift_dynamic_color_file_in_display_p3() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      mode: RenderMode::DynamicColor,
+      color_space: ColorSpace::DisplayP3,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(
+    !dir_diff::is_different(tmp_dir.path(), "tests/fixtures/dynamic_color-display-p3").unwrap()
+  );
+}
+
+#[test]
+fn generate_swift_namespaced_colorset_file() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      asset_naming: AssetNaming::Namespaced,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(!dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-namespaced").unwrap());
+}
+
+#[test]
+fn render_swift_colorset_file_in_asset_namespace() {
+  let contents = render_swift(
+    &test_document(),
+    &Options {
+      asset_naming: AssetNaming::Namespaced,
+      asset_namespace: Some("GeneratedColors".to_string()),
+      ..Default::default()
+    },
+  )
+  .expect("Could not render Swift file");
+  assert!(contents.contains(
+    "UIColor(named: \"GeneratedColors/NumericInput/NumericKey/Background\", in: BundleToken.bundle, compatibleWith: nil)!"
+  ));
+  assert!(!contents.contains("UIColor(named: \"NumericInput/"));
+}
+
+#[test]
+fn generate_swift_lower_camel_case_colorset_file() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    &Options {
+      swift_naming: SwiftNaming::LowerCamelCase,
+      ..Default::default()
+    },
     true,
-    AccessLevel::Public,
+    false,
   )
   .expect("Could not write Swift file");
-  assert!(!dir_diff::is_different(&tmp_dir.path(), "tests/fixtures/dynamic_color-public").unwrap());
+  assert!(
+    !dir_diff::is_different(tmp_dir.path(), "tests/fixtures/colorset-lower-camel-case").unwrap()
+  );
+}
+
+#[test]
+fn generate_swift_standalone_enum_dynamic_color_file() {
+  let tmp_dir = TempDir::new("generate_swift_file").expect("Create temp dir failed");
+
+  gen_swift(
+    &test_document(),
+    tmp_dir.path().join("Palette.swift"),
+    &Options {
+      mode: RenderMode::DynamicColor,
+      root_name: "Palette".to_string(),
+      root_type: RootType::Enum,
+      ..Default::default()
+    },
+    true,
+    false,
+  )
+  .expect("Could not write Swift file");
+  assert!(
+    !dir_diff::is_different(tmp_dir.path(), "tests/fixtures/dynamic_color-standalone").unwrap()
+  );
+}
+
+#[test]
+fn configure_root_namespace() {
+  let contents = "Text {\n Primary: #ffffff\n }";
+
+  let extension = render(
+    contents,
+    &Options {
+      access_level: AccessLevel::Public,
+      root_name: "Payments".to_string(),
+      root_type: RootType::Extension("NSColor".to_string()),
+      ..Default::default()
+    },
+  )
+  .expect("Could not render Swift file");
+  assert!(
+    extension.contains("extension NSColor {\n  public enum Payments {\n    public enum Text {\n")
+  );
+
+  let standalone = render(
+    contents,
+    &Options {
+      root_name: "Palette".to_string(),
+      root_type: RootType::Struct,
+      ..Default::default()
+    },
+  )
+  .expect("Could not render Swift file");
+  assert!(!standalone.contains("extension"));
+  assert!(standalone
+    .contains("\nstruct Palette {\n  enum Text {\n    /// #FFFFFF\n    static let Primary = "));
+  assert!(standalone.contains("\n  }\n}\n\nprivate final class BundleToken"));
+}
+
+#[test]
+fn deprecate_declarations() {
+  let contents = r#"
+    Text {
+      @renamed(Text.body-text)
+      Primary: #151618
+      @deprecated("Will be removed in 2.0")
+      Secondary: #85868A
+      body-text: #151618
+    }
+  "#;
+
+  let contents = render(contents, &Options::default()).expect("Could not render Swift file");
+  assert!(contents.contains(
+    "      /// #151618\n      @available(*, deprecated, renamed: \"Text.bodyText\")\n      static let Primary = Custom.Text.bodyText\n"
+  ));
+  assert!(contents.contains(
+    "      @available(*, deprecated, message: \"Will be removed in 2.0\")\n      static let Secondary = UIColor(named: \"TextSecondary\""
+  ));
+
+  let contents = render(
+    "@renamed(Body)\nPrimary: #151618\nBody: #151618",
+    &Options {
+      mode: RenderMode::DynamicColor,
+      ..Default::default()
+    },
+  )
+  .expect("Could not render Swift file");
+  assert!(contents.contains("    static let Body = dynamicColor(ColorSets[0])\n"));
+  assert!(contents.contains("    static let Primary = Custom.Body\n"));
+  assert!(!contents.contains("ColorSets[1]"));
+
+  match render("@renamed(Body)\nPrimary: #151618", &Options::default()) {
+    Err(Error::UnknownReplacement { path, replacement }) => {
+      assert_eq!(path, "Primary");
+      assert_eq!(replacement, "Body");
+    }
+    other => panic!("Expected UnknownReplacement, got {:?}", other),
+  }
+
+  match render(
+    "@renamed(Body)\nPrimary: #151618\nBody: #000000",
+    &Options::default(),
+  ) {
+    Err(Error::ReplacementColorsDiffer { path, replacement }) => {
+      assert_eq!(path, "Primary");
+      assert_eq!(replacement, "Body");
+    }
+    other => panic!("Expected ReplacementColorsDiffer, got {:?}", other),
+  }
+}
+
+#[test]
+fn document_renamed_declarations_with_their_replacement() {
+  let contents = r#"
+    $ink: (light: #151618, dark: #E7E8EA)
+    Text {
+      /// The old name of the primary text color
+      @renamed(Text.Primary)
+      Old: (light: #151618, dark: #E7E8EA)
+      Primary: $ink
+    }
+  "#;
+
+  let contents = render(contents, &Options::default()).expect("Could not render Swift file");
+  assert_eq!(
+    contents,
+    fs::read_to_string("tests/fixtures/colorset-renamed/UIColor+Custom.swift").unwrap()
+  );
+}
+
+#[test]
+fn registry_of_all_colors() {
+  let options = Options {
+    mode: RenderMode::DynamicColor,
+    root_name: "Palette".to_string(),
+    root_type: RootType::Struct,
+    swift_naming: SwiftNaming::LowerCamelCase,
+    registry: true,
+    ..Default::default()
+  };
+
+  let contents = render(
+    "Text {\n @deprecated\n Old: #ffffff\n Primary: #000000\n }\nbrand-blue: #1E6FFF",
+    &options,
+  )
+  .expect("Could not render Swift file");
+  assert!(contents.contains(
+    "  static let all: [(name: String, color: UIColor)] = [\n    (\"Text.Primary\", Text.primary),\n    (\"brand-blue\", brandBlue),\n  ]\n}\n"
+  ));
+
+  match render("all: #ffffff", &options) {
+    Err(Error::IdentifierCollision {
+      identifier, first, ..
+    }) => {
+      assert_eq!(identifier, "all");
+      assert_eq!(first, "all");
+    }
+    other => panic!("Expected IdentifierCollision, got {:?}", other),
+  }
+}
+
+#[test]
+fn escape_swift_keywords() {
+  let contents = render(
+    r#"
+    Type {
+      default: #ffffff
+      class: #000000
+    }
+    1Up: #ffffff
+  "#,
+    &Options::default(),
+  )
+  .expect("Could not render Swift file");
+
+  assert!(contents.contains("    enum `Type` {\n"));
+  assert!(contents.contains("      static let `default` = UIColor(named: \"Typedefault\""));
+  assert!(contents.contains("      static let `class` = UIColor(named: \"Typeclass\""));
+  assert!(contents.contains("    static let _1Up = UIColor(named: \"1Up\""));
+
+  let contents = render(
+    r#"
+    Type {
+      Default: #ffffff
+    }
+    URLBackground: #ffffff
+  "#,
+    &Options {
+      swift_naming: SwiftNaming::LowerCamelCase,
+      ..Default::default()
+    },
+  )
+  .expect("Could not render Swift file");
+
+  assert!(contents.contains("    enum `Type` {\n"));
+  assert!(contents.contains("      static let `default` = UIColor(named: \"TypeDefault\""));
+  assert!(contents.contains("    static let urlBackground = UIColor(named: \"URLBackground\""));
+}
+
+#[test]
+fn map_hyphens_and_underscores() {
+  let contents = r#"
+    brand-colors {
+      brand-blue: #1E6FFF
+      grey_10: #EEEEEE
+    }
+    Button.Primary: #ffffff
+  "#;
+
+  let as_is = render(contents, &Options::default()).expect("Could not render Swift file");
+  assert!(as_is.contains("    enum brandColors {\n"));
+  assert!(as_is.contains("      static let brandBlue = UIColor(named: \"brand-colorsbrand-blue\""));
+  assert!(as_is.contains("      static let grey_10 = UIColor(named: \"brand-colorsgrey_10\""));
+  assert!(as_is.contains("      static let Primary = UIColor(named: \"ButtonPrimary\""));
+
+  let lower_camel_case = render(
+    contents,
+    &Options {
+      asset_naming: AssetNaming::Dotted,
+      swift_naming: SwiftNaming::LowerCamelCase,
+      ..Default::default()
+    },
+  )
+  .expect("Could not render Swift file");
+  assert!(lower_camel_case.contains("    enum BrandColors {\n"));
+  assert!(lower_camel_case
+    .contains("      static let brandBlue = UIColor(named: \"brand-colors.brand-blue\""));
+  assert!(
+    lower_camel_case.contains("      static let grey10 = UIColor(named: \"brand-colors.grey_10\"")
+  );
+  assert!(lower_camel_case.contains("      static let primary = UIColor(named: \"Button.Primary\""));
+
+  match render(
+    "brand-blue: #1E6FFF\nbrandBlue: #1E6FFF",
+    &Options::default(),
+  ) {
+    Err(Error::IdentifierCollision {
+      identifier,
+      first,
+      second,
+    }) => {
+      assert_eq!(identifier, "brandBlue");
+      assert_eq!(first, "brand-blue");
+      assert_eq!(second, "brandBlue");
+    }
+    other => panic!("Expected IdentifierCollision, got {:?}", other),
+  }
+}
+
+#[test]
+fn detect_identifier_collisions() {
+  let options = Options {
+    swift_naming: SwiftNaming::LowerCamelCase,
+    ..Default::default()
+  };
+
+  match render("A {\n Primary: #ffffff\n primary: #000000\n }", &options) {
+    Err(Error::IdentifierCollision {
+      identifier,
+      first,
+      second,
+    }) => {
+      assert_eq!(identifier, "primary");
+      assert_eq!(first, "A.Primary");
+      assert_eq!(second, "A.primary");
+    }
+    other => panic!("Expected IdentifierCollision, got {:?}", other),
+  }
+
+  match render("Text: #ffffff\nText {}", &Options::default()) {
+    Err(Error::IdentifierCollision { identifier, .. }) => assert_eq!(identifier, "Text"),
+    other => panic!("Expected IdentifierCollision, got {:?}", other),
+  }
+
+  assert!(render("Text: #ffffff\nText {}", &options).is_ok());
+}
+
+#[test]
+fn detect_asset_name_collisions() {
+  let contents = "AB {\n C: #ffffff\n }\nA {\n BC: #000000\n }";
+
+  match render(contents, &Options::default()) {
+    Err(Error::AssetNameCollision {
+      name,
+      first,
+      second,
+    }) => {
+      assert_eq!(name, "ABC");
+      assert_eq!(first, "A.BC");
+      assert_eq!(second, "AB.C");
+    }
+    other => panic!("Expected AssetNameCollision, got {:?}", other),
+  }
+
+  for options in [
+    Options {
+      asset_naming: AssetNaming::Namespaced,
+      ..Default::default()
+    },
+    Options {
+      mode: RenderMode::DynamicColor,
+      ..Default::default()
+    },
+  ] {
+    assert!(render(contents, &options).is_ok());
+  }
 }
 
 #[test]
@@ -74,49 +632,82 @@ fn do_not_touch_identical_file() {
 
   Command::new("cp")
     .arg("-p")
-    .arg(fs::canonicalize(&fixture_path).expect("Could not resolve fixture path"))
+    .arg(fs::canonicalize(fixture_path).expect("Could not resolve fixture path"))
     .arg(&tmp_path)
     .output()
     .expect("Could not copy file");
 
   assert!(
-    is_modification_date_equal(&fixture_path, &tmp_path),
+    is_modification_date_equal(fixture_path, &tmp_path),
     "Expected modification date to be equal after copy."
   );
 
   match gen_swift(
     &test_document(),
     &tmp_path,
-    RenderMode::ColorSet,
+    &Options::default(),
+    false,
     false,
-    AccessLevel::Internal,
   ) {
     Err(Error::FileIsIdentical { path }) => {
       assert_eq!(Path::new(&path), tmp_path);
       assert!(
-        is_modification_date_equal(&fixture_path, &tmp_path),
+        is_modification_date_equal(fixture_path, &tmp_path),
         "Expected modification date to be equal after swift_gen"
       );
     }
-    Err(Error::Parser { source }) => panic!("{}", source),
-    Err(Error::Io { source }) => panic!("Unexpected error {}", source),
-    Ok(()) => panic!("Expected Err, got Ok"),
+    Err(e) => panic!("Unexpected error {}", e),
+    Ok(_) => panic!("Expected Err, got Ok"),
   }
 
   gen_swift(
     &test_document(),
     &tmp_path,
-    RenderMode::ColorSet,
+    &Options::default(),
     true,
-    AccessLevel::Internal,
+    false,
   )
   .expect("Could not write Swift file");
   assert!(
-    !is_modification_date_equal(&tmp_path, &fixture_path),
+    !is_modification_date_equal(&tmp_path, fixture_path),
     "Expected modification date to differ after swift_gen"
   );
 }
 
+#[test]
+fn dry_run_does_not_write_file() {
+  let tmp_dir = TempDir::new("dry_run_does_not_write_file").expect("Create temp dir failed");
+  let tmp_path = tmp_dir.path().join("UIColor+Custom.swift");
+
+  let changes = gen_swift(
+    &test_document(),
+    &tmp_path,
+    &Options::default(),
+    false,
+    true,
+  )
+  .expect("Could not render Swift file");
+
+  assert!(matches!(changes.as_slice(), [Change::Created { path, .. }] if path == &tmp_path));
+  assert!(!tmp_path.exists());
+}
+
+#[test]
+fn failed_rendering_does_not_touch_file() {
+  let tmp_dir =
+    TempDir::new("failed_rendering_does_not_touch_file").expect("Create temp dir failed");
+  let tmp_path = tmp_dir.path().join("UIColor+Custom.swift");
+  fs::write(&tmp_path, "// Previous contents").unwrap();
+
+  let doc = parse_document("A: $missing".to_string()).expect("Could not parse document");
+
+  assert!(gen_swift(&doc, &tmp_path, &Options::default(), true, false,).is_err());
+  assert_eq!(
+    fs::read_to_string(&tmp_path).unwrap(),
+    "// Previous contents"
+  );
+}
+
 fn test_document() -> Document {
   let contents = r#"
     $white: #ffffff
@@ -129,8 +720,10 @@ fn test_document() -> Document {
 
     $grey1: $black
 
+    /// Colors for text and glyphs.
     Text {
       Primary: (light: #151618, dark: #E7E8EA)
+      /// Used for captions and placeholders.
       Secondary: (light: $grey1, dark: #85868A)
     }
 
@@ -158,6 +751,24 @@ fn test_document() -> Document {
   parse_document(contents.to_string()).expect("Could not parse document")
 }
 
+fn render(contents: &str, options: &Options) -> Result<String, Error> {
+  let tmp_dir = TempDir::new("render").expect("Create temp dir failed");
+  let doc = parse_document(contents.to_string()).expect("Could not parse document");
+
+  match gen_swift(
+    &doc,
+    tmp_dir.path().join("UIColor+Custom.swift"),
+    options,
+    true,
+    true,
+  )?
+  .pop()
+  {
+    Some(Change::Created { new, .. }) => Ok(String::from_utf8(new).unwrap()),
+    other => panic!("Expected created file, got {:?}", other),
+  }
+}
+
 fn is_modification_date_equal<P1: AsRef<Path>, P2: AsRef<Path>>(p1: P1, p2: P2) -> bool {
   let old_metadata = fs::metadata(p1).expect("Could not read metadata of file 1");
   let new_metadata = fs::metadata(p2).expect("Could not read metadata of file 2");
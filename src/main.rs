@@ -1,8 +1,14 @@
+mod report;
+
 use anyhow::Result;
-use asset_catalog::write_asset_catalog;
-use clap::{Parser, Subcommand};
+use asset_catalog::{write_asset_catalog, write_asset_catalog_group};
+use clap::{Args, Parser, Subcommand};
 use colored::*;
-use parser::parse_document_from_file;
+use parser::ast::Document;
+use parser::{parse_document, parse_document_from_file, Gamut};
+use report::{Diagnostic, ExitCode, MessageFormat, Reporter};
+use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use swift_gen::gen_swift;
 
@@ -21,6 +27,36 @@ enum AccessLevel {
   Public,
 }
 
+#[derive(clap::ValueEnum, Clone, Default)]
+enum AssetNaming {
+  #[default]
+  Concatenated,
+  Namespaced,
+  Dotted,
+}
+
+#[derive(clap::ValueEnum, Clone, Default)]
+enum SwiftNaming {
+  #[default]
+  AsIs,
+  LowerCamelCase,
+}
+
+#[derive(clap::ValueEnum, Clone, Default)]
+enum Bundle {
+  #[default]
+  Token,
+  Main,
+  Module,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum Standalone {
+  Enum,
+  Struct,
+}
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(clap::ValueEnum, Clone, Default)]
 enum ColorSpace {
   DisplayP3,
@@ -32,6 +68,89 @@ enum ColorSpace {
   ExtendedRangeLinearSRGB,
 }
 
+#[allow(clippy::upper_case_acronyms)]
+#[derive(clap::ValueEnum, Clone, Default)]
+enum SwiftColorSpace {
+  #[default]
+  SRGB,
+  DisplayP3,
+}
+
+#[derive(clap::ValueEnum, Clone, Default)]
+enum PreviewFormat {
+  #[default]
+  Html,
+  Svg,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum DiffFormat {
+  Text,
+  Json,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy)]
+enum LintRule {
+  Contrast,
+  UnusedVariable,
+  RedundantColorSet,
+  HardcodedColor,
+  NearDuplicateColor,
+}
+
+#[derive(Args)]
+struct SwiftArgs {
+  /// Specify if the generated code should reference the asset catalog or create dynamic colors programmatically
+  #[arg(name = "mode", value_enum, default_value_t, long, short)]
+  render_mode: RenderMode,
+  /// The access level for the generated code
+  #[arg(name = "access", value_enum, default_value_t, long, short)]
+  access_level: AccessLevel,
+  /// How colors and rulesets are named in Swift
+  #[arg(value_enum, default_value_t, long)]
+  naming: SwiftNaming,
+  /// The name of the type all colors are nested in (e.g. UIColor.Custom)
+  #[arg(long, default_value = "Custom")]
+  root_name: String,
+  /// The type extended with the root namespace
+  #[arg(long, default_value = "UIColor")]
+  extend: String,
+  /// Generate a standalone top-level type instead of an extension
+  #[arg(value_enum, long, conflicts_with = "extend")]
+  standalone: Option<Standalone>,
+  /// The bundle colors are loaded from in asset-catalog mode
+  #[arg(value_enum, default_value_t, long)]
+  bundle: Bundle,
+  /// A Swift expression evaluating to the bundle colors are loaded from (e.g. ResourceBundle.colors)
+  #[arg(long, conflicts_with = "bundle")]
+  bundle_expression: Option<String>,
+  /// A Swift expression used if a color is missing from the Asset catalog instead of force-unwrapping it (e.g. .magenta)
+  #[arg(long)]
+  fallback_color: Option<String>,
+  /// Trigger an assertionFailure if a color is missing from the Asset catalog instead of force-unwrapping it
+  #[arg(long, conflicts_with = "fallback_color")]
+  assert_missing_colors: bool,
+  /// Add a list of all colors and their names to the root namespace (e.g. for a design system gallery)
+  #[arg(long)]
+  registry: bool,
+  /// The color space the colors are converted into in dynamic-color mode
+  #[arg(value_enum, default_value_t, long)]
+  dynamic_color_space: SwiftColorSpace,
+  /// The name of the group folder passed to gen-assets with --provides-namespace (e.g. GeneratedColors)
+  #[arg(long, value_name = "NAME")]
+  asset_namespace: Option<String>,
+}
+
+#[derive(Args)]
+struct DryRunArgs {
+  /// Print the files that would be created, changed or deleted without writing anything
+  #[arg(long)]
+  dry_run: bool,
+  /// Print a unified diff for every file that would be changed
+  #[arg(long, requires = "dry_run")]
+  diff: bool,
+}
+
 #[derive(Parser)]
 #[command(
   author,
@@ -43,6 +162,10 @@ enum ColorSpace {
 struct Cli {
   #[clap(subcommand)]
   cmd: Command,
+  /// How results and errors are printed. "json" prints one event per line, "xcode" prints
+  /// diagnostics Xcode shows inline when run in a build phase
+  #[arg(value_enum, default_value_t, long, global = true)]
+  message_format: MessageFormat,
 }
 
 #[derive(Subcommand)]
@@ -55,12 +178,13 @@ enum Command {
     /// Sets the output filename (e.g. Colors.xcassets)
     #[arg(short)]
     output_path: PathBuf,
-    /// Specify if the generated code should reference the asset catalog or create dynamic colors programmatically
-    #[arg(name = "mode", value_enum, default_value_t, long, short)]
-    render_mode: RenderMode,
-    /// The access level for the generated code
-    #[arg(name = "access", value_enum, default_value_t, long, short)]
-    access_level: AccessLevel,
+    /// How colors are named in the Asset catalog. Must match the value passed to gen-assets
+    #[arg(value_enum, default_value_t, long)]
+    asset_naming: AssetNaming,
+    #[command(flatten)]
+    swift: SwiftArgs,
+    #[command(flatten)]
+    dry_run: DryRunArgs,
   },
   /// generates the Asset Catalog
   #[command(name = "gen-assets")]
@@ -73,104 +197,599 @@ enum Command {
     /// Specify which colorspace to use
     #[arg(value_enum, default_value_t, long, short)]
     color_space: ColorSpace,
+    /// Convert the sRGB colors into the components of the colorspace instead of only labeling them with it
+    #[arg(long)]
+    convert_colors: bool,
     /// Overwrite Asset catalog if it already exists
-    #[arg(name = "force", long, short)]
+    #[arg(name = "force", long, short, conflicts_with = "group")]
     overwrite_asset_catalog: bool,
+    /// Treat the output path as a group folder inside an existing Asset catalog (e.g. Assets.xcassets/Colors)
+    #[arg(long, short)]
+    group: bool,
+    /// Mark the group folder as providing a namespace for the colors it contains
+    #[arg(long, requires = "group")]
+    provides_namespace: bool,
+    /// How colors are named. With "namespaced" ruleset folders provide a namespace
+    #[arg(value_enum, default_value_t, long)]
+    asset_naming: AssetNaming,
+    #[command(flatten)]
+    dry_run: DryRunArgs,
+  },
+  /// checks that generated Swift code and Asset catalogs are up to date, without writing anything
+  Check {
+    /// Sets the input file
+    input_file: PathBuf,
+    /// The generated Swift file to check
+    #[arg(long, value_name = "PATH", required_unless_present = "assets")]
+    swift: Option<PathBuf>,
+    /// The generated Asset catalog to check
+    #[arg(long, value_name = "PATH")]
+    assets: Option<PathBuf>,
+    /// Treat the Asset catalog path as a group folder inside an existing Asset catalog
+    #[arg(long, requires = "assets")]
+    group: bool,
+    /// Whether the group folder provides a namespace for the colors it contains
+    #[arg(long, requires = "group")]
+    provides_namespace: bool,
+    /// The colorspace passed to gen-assets
+    #[arg(value_enum, default_value_t, long, short)]
+    color_space: ColorSpace,
+    /// Whether --convert-colors has been passed to gen-assets
+    #[arg(long, requires = "assets")]
+    convert_colors: bool,
+    /// How colors are named in the Asset catalog
+    #[arg(value_enum, default_value_t, long)]
+    asset_naming: AssetNaming,
+    #[command(flatten)]
+    swift_args: SwiftArgs,
+    /// Print a unified diff for every file that is out of date
+    #[arg(long)]
+    diff: bool,
+  },
+  /// checks the stylesheet for issues, e.g. insufficient contrast
+  Lint {
+    /// Sets the input file
+    input_file: PathBuf,
+    /// Disable a rule
+    #[arg(value_enum, long, value_name = "RULE")]
+    allow: Vec<LintRule>,
+    /// Report violations of a rule as warnings
+    #[arg(value_enum, long, value_name = "RULE")]
+    warn: Vec<LintRule>,
+    /// Report violations of a rule as errors, failing the command
+    #[arg(value_enum, long, value_name = "RULE")]
+    deny: Vec<LintRule>,
+    /// Flag colors as near-duplicates if their color difference (ΔE) is below this value
+    #[arg(long, default_value_t = lint::Config::default().near_duplicate_threshold)]
+    near_duplicate_threshold: f32,
+  },
+  /// renders a swatch sheet of all colors for review in a browser
+  Preview {
+    /// Sets the input file
+    input_file: PathBuf,
+    /// Sets the output filename (e.g. colors.html)
+    #[arg(short)]
+    output_path: PathBuf,
+    /// The file format of the swatch sheet
+    #[arg(value_enum, default_value_t, long)]
+    format: PreviewFormat,
   },
+  /// compares the resolved colors of two versions of a stylesheet
+  Diff {
+    /// The old version of the stylesheet
+    old_file: PathBuf,
+    /// The new version of the stylesheet
+    input_file: PathBuf,
+    /// How the differences are printed, json with --message-format json and text otherwise
+    #[arg(value_enum, long)]
+    format: Option<DiffFormat>,
+  },
+  /// prints the stylesheet as JSON
+  Dump {
+    /// Sets the input file
+    input_file: PathBuf,
+    /// Print the declarations with all variables resolved instead of the syntax tree
+    #[arg(long)]
+    resolved: bool,
+  },
+}
+
+impl SwiftArgs {
+  fn options(self, asset_naming: AssetNaming) -> swift_gen::Options {
+    swift_gen::Options {
+      mode: self.render_mode.into(),
+      access_level: self.access_level.into(),
+      asset_naming: asset_naming.into(),
+      asset_namespace: self.asset_namespace,
+      swift_naming: self.naming.into(),
+      root_name: self.root_name,
+      root_type: match self.standalone {
+        None => swift_gen::RootType::Extension(self.extend),
+        Some(Standalone::Enum) => swift_gen::RootType::Enum,
+        Some(Standalone::Struct) => swift_gen::RootType::Struct,
+      },
+      bundle: match self.bundle_expression {
+        Some(expression) => swift_gen::BundleStrategy::Custom(expression),
+        None => self.bundle.into(),
+      },
+      missing_color: match self.fallback_color {
+        Some(expression) => swift_gen::MissingColor::Fallback(expression),
+        None if self.assert_missing_colors => swift_gen::MissingColor::AssertionFailure,
+        None => swift_gen::MissingColor::ForceUnwrap,
+      },
+      registry: self.registry,
+      color_space: self.dynamic_color_space.into(),
+    }
+  }
+}
+
+impl Command {
+  fn input_file(&self) -> &Path {
+    match self {
+      Command::GenerateSwiftCode { input_file, .. }
+      | Command::GenerateAssetCatalog { input_file, .. }
+      | Command::Check { input_file, .. }
+      | Command::Lint { input_file, .. }
+      | Command::Preview { input_file, .. }
+      | Command::Diff { input_file, .. }
+      | Command::Dump { input_file, .. } => input_file,
+    }
+  }
 }
 
 fn main() {
-  let result = match Cli::parse().cmd {
+  let cli = Cli::parse();
+  let reporter = Reporter::new(cli.message_format);
+  let input_file = cli.cmd.input_file().to_path_buf();
+
+  let result = match cli.cmd {
     Command::GenerateSwiftCode {
       input_file,
       output_path,
-      render_mode,
-      access_level,
-    } => generate_swift_code(input_file, output_path, render_mode, access_level),
+      asset_naming,
+      swift,
+      dry_run,
+    } => generate_swift_code(
+      input_file,
+      output_path,
+      swift.options(asset_naming),
+      dry_run,
+      &reporter,
+    ),
     Command::GenerateAssetCatalog {
       input_file,
       output_path,
       color_space,
+      convert_colors,
       overwrite_asset_catalog,
-    } => generate_asset_catalog(
+      group,
+      provides_namespace,
+      asset_naming,
+      dry_run,
+    } => {
+      let options = asset_catalog::Options {
+        color_space: color_space.into(),
+        naming: asset_naming.into(),
+        convert_colors,
+      };
+      if group {
+        generate_asset_catalog_group(
+          input_file,
+          output_path,
+          options,
+          provides_namespace,
+          dry_run,
+          &reporter,
+        )
+      } else {
+        generate_asset_catalog(
+          input_file,
+          output_path,
+          options,
+          overwrite_asset_catalog,
+          dry_run,
+          &reporter,
+        )
+      }
+    }
+    Command::Check {
       input_file,
-      output_path,
+      swift,
+      assets,
+      group,
+      provides_namespace,
       color_space,
-      overwrite_asset_catalog,
-    ),
+      convert_colors,
+      asset_naming,
+      mut swift_args,
+      diff,
+    } => {
+      // The group folder providing a namespace prefixes the names the Swift code looks up, unless
+      // the namespace has been passed explicitly.
+      if swift_args.asset_namespace.is_none() {
+        swift_args.asset_namespace = assets
+          .as_ref()
+          .filter(|_| provides_namespace)
+          .and_then(|path| path.file_name())
+          .map(|name| name.to_string_lossy().into_owned());
+      }
+      check_outputs(
+        input_file,
+        swift.map(|path| (path, swift_args.options(asset_naming.clone()))),
+        assets.map(|path| AssetCatalogOutput {
+          path,
+          group,
+          provides_namespace,
+          options: asset_catalog::Options {
+            color_space: color_space.into(),
+            naming: asset_naming.into(),
+            convert_colors,
+          },
+        }),
+        diff,
+        &reporter,
+      )
+    }
+    Command::Lint {
+      input_file,
+      allow,
+      warn,
+      deny,
+      near_duplicate_threshold,
+    } => {
+      let mut config = lint::Config::default();
+      config.near_duplicate_threshold = near_duplicate_threshold;
+      for (rules, severity) in [
+        (allow, lint::Severity::Allow),
+        (warn, lint::Severity::Warning),
+        (deny, lint::Severity::Error),
+      ] {
+        for rule in rules {
+          config.set_severity(rule.into(), severity);
+        }
+      }
+      lint_stylesheet(input_file, &config, &reporter)
+    }
+    Command::Preview {
+      input_file,
+      output_path,
+      format,
+    } => render_preview(input_file, output_path, format.into(), &reporter),
+    Command::Diff {
+      old_file,
+      input_file,
+      format,
+    } => {
+      // The differences are the output of the command, so they follow the message format too.
+      let format = format.unwrap_or(match cli.message_format {
+        MessageFormat::Json => DiffFormat::Json,
+        _ => DiffFormat::Text,
+      });
+      diff_stylesheets(old_file, input_file, format, &reporter)
+    }
+    Command::Dump {
+      input_file,
+      resolved,
+    } => dump_document(input_file, resolved),
   };
+
   match result {
-    Ok(_) => (),
+    Ok(code) => code.exit(),
     Err(e) => {
-      println!("{}", format!("{}", e).red());
-      std::process::exit(0x0100);
+      reporter.diagnostic(&Diagnostic::for_error(&e, &input_file));
+      ExitCode::for_error(&e).exit()
     }
   }
 }
 
+/// Parses the stylesheet at `input_file` and warns about colors which are outside of the `gamut`
+/// they are generated in. Returns the stylesheet along with its source.
+fn parse_stylesheet(
+  input_file: &Path,
+  gamut: Gamut,
+  reporter: &Reporter,
+) -> Result<(Document, String)> {
+  let source = fs::read_to_string(input_file)?;
+  let doc = parse_document(source.clone())?;
+
+  for warning in parser::gamut_warnings(&doc, &source, gamut) {
+    reporter.diagnostic(&Diagnostic::for_warning(&warning, input_file, &source));
+  }
+  Ok((doc, source))
+}
+
+/// The gamut of the colors of a generated Swift file. Dynamic colors in Display P3 are converted
+/// from the original components of colors outside of sRGB.
+fn swift_gamut(options: &swift_gen::Options) -> Gamut {
+  if options.mode == swift_gen::RenderMode::DynamicColor
+    && options.color_space == swift_gen::ColorSpace::DisplayP3
+  {
+    Gamut::DisplayP3
+  } else {
+    Gamut::SRGB
+  }
+}
+
+/// The gamut of the colors of a generated asset catalog.
+fn asset_catalog_gamut(options: &asset_catalog::Options) -> Gamut {
+  if options.convert_colors && options.color_space == asset_catalog::ColorSpace::DisplayP3 {
+    Gamut::DisplayP3
+  } else {
+    Gamut::SRGB
+  }
+}
+
 fn generate_swift_code(
   input_file: impl AsRef<Path>,
   output_path: impl AsRef<Path>,
-  render_mode: RenderMode,
-  access_level: AccessLevel,
-) -> Result<()> {
-  let doc = parse_document_from_file(input_file)?;
+  options: swift_gen::Options,
+  dry_run: DryRunArgs,
+  reporter: &Reporter,
+) -> Result<ExitCode> {
+  let (doc, _) = parse_stylesheet(input_file.as_ref(), swift_gamut(&options), reporter)?;
   let output_path = output_path.as_ref();
 
-  match gen_swift(
-    &doc,
-    output_path,
-    render_mode.into(),
-    false,
-    access_level.into(),
-  ) {
-    Err(e @ swift_gen::Error::FileIsIdentical { .. }) => {
-      println!("{}", format!("{}", e).dimmed())
-    }
+  match gen_swift(&doc, output_path, &options, false, dry_run.dry_run) {
+    Err(swift_gen::Error::FileIsIdentical { path }) => reporter.unchanged(&path),
     Err(e) => return Err(anyhow::Error::new(e)),
-    Ok(_) => println!(
-      "{}",
-      format!("Generated Swift file at {}.", output_path.display()).green()
-    ),
+    Ok(changes) if dry_run.dry_run => {
+      reporter.planned_changes(Path::new(""), &changes, dry_run.diff)
+    }
+    Ok(changes) => reporter.written(output_path, &changes, "Swift file"),
   }
 
-  Ok(())
+  Ok(ExitCode::Success)
 }
 
 fn generate_asset_catalog(
   input_file: impl AsRef<Path>,
   output_path: impl AsRef<Path>,
-  color_space: ColorSpace,
+  options: asset_catalog::Options,
   overwrite_asset_catalog: bool,
-) -> Result<()> {
-  let doc = parse_document_from_file(input_file)?;
+  dry_run: DryRunArgs,
+  reporter: &Reporter,
+) -> Result<ExitCode> {
+  let gamut = asset_catalog_gamut(&options);
+  let (doc, _) = parse_stylesheet(input_file.as_ref(), gamut, reporter)?;
   let output_path = output_path.as_ref();
 
-  match write_asset_catalog(
+  let changes = write_asset_catalog(
     &doc,
     output_path,
-    color_space.into(),
+    &options,
     overwrite_asset_catalog,
-  ) {
-    Err(asset_catalog::Error::CatalogExists { .. }) => {
-      println!(
-        "{}",
-        format!(
-          "Asset catalog at {} already exists. Use -f to overwrite it.",
-          output_path.display()
-        )
-        .yellow()
-      );
-      std::process::exit(0x0100);
+    dry_run.dry_run,
+  )
+  .map_err(|e| match e {
+    asset_catalog::Error::CatalogExists { .. } => anyhow::Error::new(e).context(format!(
+      "Asset catalog at {} already exists. Use -f to overwrite it.",
+      output_path.display()
+    )),
+    e => anyhow::Error::new(e),
+  })?;
+
+  if dry_run.dry_run {
+    reporter.planned_changes(output_path, &changes, dry_run.diff);
+  } else {
+    reporter.written(output_path, &changes, "Asset catalog");
+  }
+
+  Ok(ExitCode::Success)
+}
+
+fn generate_asset_catalog_group(
+  input_file: impl AsRef<Path>,
+  output_path: impl AsRef<Path>,
+  options: asset_catalog::Options,
+  provides_namespace: bool,
+  dry_run: DryRunArgs,
+  reporter: &Reporter,
+) -> Result<ExitCode> {
+  let gamut = asset_catalog_gamut(&options);
+  let (doc, _) = parse_stylesheet(input_file.as_ref(), gamut, reporter)?;
+  let output_path = output_path.as_ref();
+
+  let changes = write_asset_catalog_group(
+    &doc,
+    output_path,
+    &options,
+    provides_namespace,
+    dry_run.dry_run,
+  )?;
+
+  if dry_run.dry_run {
+    reporter.planned_changes(output_path, &changes, dry_run.diff);
+  } else {
+    reporter.written(output_path, &changes, "Asset catalog group");
+  }
+
+  Ok(ExitCode::Success)
+}
+
+struct AssetCatalogOutput {
+  path: PathBuf,
+  group: bool,
+  provides_namespace: bool,
+  options: asset_catalog::Options,
+}
+
+fn check_outputs(
+  input_file: impl AsRef<Path>,
+  swift: Option<(PathBuf, swift_gen::Options)>,
+  assets: Option<AssetCatalogOutput>,
+  diff: bool,
+  reporter: &Reporter,
+) -> Result<ExitCode> {
+  // Colors are checked against the narrowest gamut of the outputs.
+  let gamut = [
+    swift.as_ref().map(|(_, options)| swift_gamut(options)),
+    assets
+      .as_ref()
+      .map(|assets| asset_catalog_gamut(&assets.options)),
+  ]
+  .into_iter()
+  .flatten()
+  .min()
+  .unwrap_or_default();
+  let (doc, _) = parse_stylesheet(input_file.as_ref(), gamut, reporter)?;
+  let mut is_stale = false;
+
+  if let Some((path, options)) = swift {
+    match gen_swift(&doc, &path, &options, false, true) {
+      Err(swift_gen::Error::FileIsIdentical { path }) => reporter.unchanged(&path),
+      Err(e) => return Err(anyhow::Error::new(e)),
+      Ok(changes) => {
+        is_stale = true;
+        reporter.stale_files(Path::new(""), &changes, diff);
+      }
     }
-    Err(e) => return Err(anyhow::Error::new(e)),
-    Ok(_) => println!(
-      "{}",
-      format!("Generated Asset catalog at {}.", output_path.display()).green()
-    ),
   }
 
-  Ok(())
+  if let Some(assets) = assets {
+    let changes = if assets.group {
+      write_asset_catalog_group(
+        &doc,
+        &assets.path,
+        &assets.options,
+        assets.provides_namespace,
+        true,
+      )?
+    } else {
+      write_asset_catalog(&doc, &assets.path, &assets.options, true, true)?
+    };
+
+    if changes.is_empty() {
+      reporter.unchanged(&assets.path);
+    } else {
+      is_stale = true;
+      reporter.stale_files(&assets.path, &changes, diff);
+    }
+  }
+
+  if is_stale {
+    reporter.status(
+      "Generated files are out of date. Run gen-swift and gen-assets to update them.".red(),
+    );
+    return Ok(ExitCode::OutdatedOutputs);
+  }
+  Ok(ExitCode::Success)
+}
+
+fn lint_stylesheet(
+  input_file: impl AsRef<Path>,
+  config: &lint::Config,
+  reporter: &Reporter,
+) -> Result<ExitCode> {
+  let input_file = input_file.as_ref();
+  let (doc, source) = parse_stylesheet(input_file, Gamut::SRGB, reporter)?;
+  let issues = lint::lint(&doc, &source, config)?;
+
+  for issue in &issues {
+    reporter.diagnostic(&Diagnostic::for_issue(issue, input_file, &source));
+  }
+  if issues.is_empty() {
+    reporter.status("No issues found.".green());
+  } else {
+    reporter.status(format!("Found {} issue(s).", issues.len()).normal());
+  }
+
+  if issues
+    .iter()
+    .any(|issue| issue.severity == lint::Severity::Error)
+  {
+    return Ok(ExitCode::LintErrors);
+  }
+  Ok(ExitCode::Success)
+}
+
+fn render_preview(
+  input_file: impl AsRef<Path>,
+  output_path: impl AsRef<Path>,
+  format: preview::Format,
+  reporter: &Reporter,
+) -> Result<ExitCode> {
+  let (doc, _) = parse_stylesheet(input_file.as_ref(), Gamut::SRGB, reporter)?;
+  let output_path = output_path.as_ref();
+
+  let changes = preview::write_preview(&doc, output_path, format)?;
+  reporter.written(output_path, &changes, "preview");
+
+  Ok(ExitCode::Success)
+}
+
+fn diff_stylesheets(
+  old_file: impl AsRef<Path>,
+  input_file: impl AsRef<Path>,
+  format: DiffFormat,
+  reporter: &Reporter,
+) -> Result<ExitCode> {
+  let old_file = old_file.as_ref();
+  // Errors returned from here are located in `input_file`, so errors in the old version are
+  // reported right away.
+  let old = match parse_stylesheet(old_file, Gamut::SRGB, reporter) {
+    Ok((doc, _)) => doc,
+    Err(e) => {
+      reporter.diagnostic(&Diagnostic::for_error(&e, old_file));
+      return Ok(ExitCode::for_error(&e));
+    }
+  };
+  let (new, _) = parse_stylesheet(input_file.as_ref(), Gamut::SRGB, reporter)?;
+  let diff = diff::Diff::between(&old, &new)?;
+  let mut stdout = io::stdout().lock();
+
+  match format {
+    DiffFormat::Text => write!(stdout, "{}", diff)?,
+    DiffFormat::Json => {
+      serde_json::to_writer_pretty(&mut stdout, &diff.to_json())?;
+      writeln!(stdout)?;
+    }
+  }
+
+  Ok(ExitCode::Success)
+}
+
+/// The envelope of the JSON printed by the dump command.
+#[derive(serde::Serialize)]
+struct Dump<T> {
+  version: u32,
+  kind: &'static str,
+  document: T,
+}
+
+fn dump_document(input_file: impl AsRef<Path>, resolved: bool) -> Result<ExitCode> {
+  let doc = parse_document_from_file(input_file)?;
+  let mut stdout = io::stdout().lock();
+
+  if resolved {
+    let dump = Dump {
+      version: parser::SCHEMA_VERSION,
+      kind: "resolved",
+      document: parser::resolved::Document::derive_from(&doc)?,
+    };
+    serde_json::to_writer_pretty(&mut stdout, &dump)?;
+  } else {
+    let dump = Dump {
+      version: parser::SCHEMA_VERSION,
+      kind: "ast",
+      document: doc,
+    };
+    serde_json::to_writer_pretty(&mut stdout, &dump)?;
+  }
+  writeln!(stdout)?;
+
+  Ok(ExitCode::Success)
+}
+
+impl From<LintRule> for lint::Rule {
+  fn from(value: LintRule) -> Self {
+    match value {
+      LintRule::Contrast => lint::Rule::Contrast,
+      LintRule::UnusedVariable => lint::Rule::UnusedVariable,
+      LintRule::RedundantColorSet => lint::Rule::RedundantColorSet,
+      LintRule::HardcodedColor => lint::Rule::HardcodedColor,
+      LintRule::NearDuplicateColor => lint::Rule::NearDuplicateColor,
+    }
+  }
 }
 
 impl From<RenderMode> for swift_gen::RenderMode {
@@ -191,6 +810,35 @@ impl From<AccessLevel> for swift_gen::AccessLevel {
   }
 }
 
+impl From<Bundle> for swift_gen::BundleStrategy {
+  fn from(value: Bundle) -> Self {
+    match value {
+      Bundle::Token => swift_gen::BundleStrategy::Token,
+      Bundle::Main => swift_gen::BundleStrategy::Main,
+      Bundle::Module => swift_gen::BundleStrategy::Module,
+    }
+  }
+}
+
+impl From<AssetNaming> for parser::AssetNaming {
+  fn from(value: AssetNaming) -> Self {
+    match value {
+      AssetNaming::Concatenated => parser::AssetNaming::Concatenated,
+      AssetNaming::Namespaced => parser::AssetNaming::Namespaced,
+      AssetNaming::Dotted => parser::AssetNaming::Dotted,
+    }
+  }
+}
+
+impl From<SwiftNaming> for swift_gen::SwiftNaming {
+  fn from(value: SwiftNaming) -> Self {
+    match value {
+      SwiftNaming::AsIs => swift_gen::SwiftNaming::AsIs,
+      SwiftNaming::LowerCamelCase => swift_gen::SwiftNaming::LowerCamelCase,
+    }
+  }
+}
+
 impl From<ColorSpace> for asset_catalog::ColorSpace {
   fn from(value: ColorSpace) -> Self {
     match value {
@@ -201,3 +849,21 @@ impl From<ColorSpace> for asset_catalog::ColorSpace {
     }
   }
 }
+
+impl From<SwiftColorSpace> for swift_gen::ColorSpace {
+  fn from(value: SwiftColorSpace) -> Self {
+    match value {
+      SwiftColorSpace::SRGB => swift_gen::ColorSpace::SRGB,
+      SwiftColorSpace::DisplayP3 => swift_gen::ColorSpace::DisplayP3,
+    }
+  }
+}
+
+impl From<PreviewFormat> for preview::Format {
+  fn from(value: PreviewFormat) -> Self {
+    match value {
+      PreviewFormat::Html => preview::Format::Html,
+      PreviewFormat::Svg => preview::Format::Svg,
+    }
+  }
+}
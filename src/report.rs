@@ -0,0 +1,350 @@
+use colored::*;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use swift_gen::Change;
+
+#[derive(clap::ValueEnum, Clone, Copy, Default, PartialEq)]
+pub enum MessageFormat {
+  #[default]
+  Human,
+  Json,
+  Xcode,
+}
+
+/// The exit status of the command line tool. Invalid arguments exit with status 2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitCode {
+  /// Everything went fine. Files might have been left untouched because they are up to date.
+  Success = 0,
+  /// An unexpected error occurred.
+  Failure = 1,
+  /// The stylesheet can't be parsed or references unknown variables.
+  InvalidStylesheet = 3,
+  /// The stylesheet is valid but can't be generated, e.g. two colors would get the same name.
+  GenerationFailed = 4,
+  /// The output path can't be used, e.g. the Asset catalog exists and `--force` is missing.
+  InvalidOutputPath = 5,
+  /// Reading or writing a file failed.
+  Io = 6,
+  /// `lint` reported at least one issue with severity error.
+  LintErrors = 7,
+  /// `check` found generated files which are out of date.
+  OutdatedOutputs = 8,
+}
+
+impl ExitCode {
+  pub fn for_error(e: &anyhow::Error) -> Self {
+    if let Some(e) = e.downcast_ref::<swift_gen::Error>() {
+      return match e {
+        swift_gen::Error::Io { .. } => Self::Io,
+        swift_gen::Error::Parser { source } => Self::for_parser_error(source),
+        _ => Self::GenerationFailed,
+      };
+    }
+    if let Some(e) = e.downcast_ref::<asset_catalog::Error>() {
+      return match e {
+        asset_catalog::Error::CatalogExists { .. } | asset_catalog::Error::NotInCatalog { .. } => {
+          Self::InvalidOutputPath
+        }
+        asset_catalog::Error::DuplicateAssetName { .. } => Self::GenerationFailed,
+        asset_catalog::Error::Io(_) => Self::Io,
+        asset_catalog::Error::Parser(e) => Self::for_parser_error(e),
+      };
+    }
+    if let Some(e) = e.downcast_ref::<preview::Error>() {
+      return match e {
+        preview::Error::Io(_) => Self::Io,
+        preview::Error::Parser(e) => Self::for_parser_error(e),
+      };
+    }
+    if let Some(diff::Error::Parser(e)) = e.downcast_ref::<diff::Error>() {
+      return Self::for_parser_error(e);
+    }
+    if let Some(lint::Error::Parser(e)) = e.downcast_ref::<lint::Error>() {
+      return Self::for_parser_error(e);
+    }
+    if let Some(e) = e.downcast_ref::<parser::Error>() {
+      return Self::for_parser_error(e);
+    }
+    if e.downcast_ref::<std::io::Error>().is_some() {
+      return Self::Io;
+    }
+    Self::Failure
+  }
+
+  fn for_parser_error(e: &parser::Error) -> Self {
+    match e {
+      parser::Error::Io(_) => Self::Io,
+      _ => Self::InvalidStylesheet,
+    }
+  }
+
+  pub fn exit(self) -> ! {
+    std::process::exit(self as i32)
+  }
+}
+
+/// An error or warning, optionally pointing at a location in the stylesheet.
+pub struct Diagnostic {
+  pub severity: lint::Severity,
+  pub rule: Option<lint::Rule>,
+  pub file: Option<PathBuf>,
+  /// The 1-based line and column.
+  pub location: Option<(usize, usize)>,
+  pub message: String,
+  /// A more elaborate description spanning multiple lines, e.g. the trace of a parse error.
+  pub details: Option<String>,
+}
+
+impl Diagnostic {
+  pub fn for_issue(issue: &lint::Issue, file: &Path, source: &str) -> Self {
+    Diagnostic {
+      severity: issue.severity,
+      rule: Some(issue.rule),
+      file: Some(file.into()),
+      location: Some(issue.span.line_column(source)),
+      message: issue.message.clone(),
+      details: None,
+    }
+  }
+
+  pub fn for_warning(warning: &parser::Warning, file: &Path, source: &str) -> Self {
+    Diagnostic {
+      severity: lint::Severity::Warning,
+      rule: None,
+      file: Some(file.into()),
+      location: Some(warning.span.line_column(source)),
+      message: warning.message.clone(),
+      details: None,
+    }
+  }
+
+  /// Describes `e`, which occurred while processing the stylesheet at `input_file`.
+  pub fn for_error(e: &anyhow::Error, input_file: &Path) -> Self {
+    let file = match ExitCode::for_error(e) {
+      ExitCode::InvalidStylesheet | ExitCode::GenerationFailed => Some(input_file.to_path_buf()),
+      _ => None,
+    };
+    let mut diagnostic = Diagnostic {
+      severity: lint::Severity::Error,
+      rule: None,
+      file,
+      location: None,
+      message: e.to_string(),
+      details: None,
+    };
+
+    let parser_error = parser_error(e);
+    if let Some(span) = parser_error.and_then(parser::Error::span) {
+      // The source isn't passed around with the error, so it is read once more to locate it.
+      diagnostic.location = fs::read_to_string(input_file)
+        .ok()
+        .map(|source| span.line_column(&source));
+    }
+    if let Some(parser::Error::ParseError { summary, .. }) = parser_error {
+      diagnostic.message = summary.clone();
+      diagnostic.details = Some(e.to_string());
+    }
+
+    diagnostic
+  }
+}
+
+/// Returns the parser error `e` has been caused by, unwrapping the errors of the generators.
+fn parser_error(e: &anyhow::Error) -> Option<&parser::Error> {
+  if let Some(swift_gen::Error::Parser { source }) = e.downcast_ref() {
+    return Some(source);
+  }
+  if let Some(asset_catalog::Error::Parser(e)) = e.downcast_ref() {
+    return Some(e);
+  }
+  if let Some(preview::Error::Parser(e)) = e.downcast_ref() {
+    return Some(e);
+  }
+  if let Some(diff::Error::Parser(e)) = e.downcast_ref() {
+    return Some(e);
+  }
+  if let Some(lint::Error::Parser(e)) = e.downcast_ref() {
+    return Some(e);
+  }
+  e.downcast_ref()
+}
+
+/// Prints the outcome of a command in the requested `MessageFormat`.
+pub struct Reporter {
+  format: MessageFormat,
+}
+
+impl Reporter {
+  pub fn new(format: MessageFormat) -> Self {
+    Reporter { format }
+  }
+
+  /// Reports `changes` which have been written to `root`. `description` names the generated
+  /// output, e.g. "Swift file".
+  pub fn written(&self, root: &Path, changes: &[Change], description: &str) {
+    match self.format {
+      MessageFormat::Json => {
+        for change in changes {
+          self.event(json!({
+            "event": "file-written",
+            "change": change.verb(),
+            "path": root.join(change.path()),
+          }));
+        }
+        if changes.is_empty() {
+          self.event(json!({ "event": "file-unchanged", "path": root }));
+        }
+      }
+      MessageFormat::Human | MessageFormat::Xcode if changes.is_empty() => self.unchanged(root),
+      MessageFormat::Human | MessageFormat::Xcode => {
+        self.status(format!("Generated {} at {}.", description, root.display()).green())
+      }
+    }
+  }
+
+  /// Reports that the file at `path` is up to date and has not been touched.
+  pub fn unchanged(&self, path: &Path) {
+    match self.format {
+      MessageFormat::Json => self.event(json!({ "event": "file-unchanged", "path": path })),
+      MessageFormat::Human | MessageFormat::Xcode => self.status(
+        format!(
+          "Contents of {} remain identical. Nothing has been touched.",
+          path.display()
+        )
+        .dimmed(),
+      ),
+    }
+  }
+
+  /// Reports the changes a dry run would have written to `root`.
+  pub fn planned_changes(&self, root: &Path, changes: &[Change], diff: bool) {
+    if self.format == MessageFormat::Json {
+      for change in changes {
+        self.event(json!({
+          "event": "planned-change",
+          "change": change.verb(),
+          "path": root.join(change.path()),
+          "diff": diff.then(|| change.unified_diff()),
+        }));
+      }
+      return;
+    }
+
+    for change in changes {
+      let line = format!("{} {}", change.verb(), root.join(change.path()).display());
+      self.status(match change {
+        Change::Created { .. } => line.green(),
+        Change::Changed { .. } => line.yellow(),
+        Change::Deleted { .. } => line.red(),
+      });
+
+      if diff {
+        print!("{}", change.unified_diff());
+      }
+    }
+
+    self.status(
+      format!(
+        "Dry run: {} file(s) would be touched, nothing has been written.",
+        changes.len()
+      )
+      .dimmed(),
+    );
+  }
+
+  /// Reports generated files in `root` which don't match the stylesheet anymore.
+  pub fn stale_files(&self, root: &Path, changes: &[Change], diff: bool) {
+    for change in changes {
+      let path = root.join(change.path());
+      let (insertions, deletions) = change.line_stats();
+
+      if self.format == MessageFormat::Json {
+        self.event(json!({
+          "event": "stale-file",
+          "change": change.verb(),
+          "path": path,
+          "insertions": insertions,
+          "deletions": deletions,
+          "diff": diff.then(|| change.unified_diff()),
+        }));
+        continue;
+      }
+
+      self.status(
+        format!(
+          "{} is out of date ({}, +{} -{})",
+          path.display(),
+          change.verb(),
+          insertions,
+          deletions
+        )
+        .yellow(),
+      );
+      if diff {
+        print!("{}", change.unified_diff());
+      }
+    }
+  }
+
+  pub fn diagnostic(&self, diagnostic: &Diagnostic) {
+    if self.format == MessageFormat::Json {
+      self.event(json!({
+        "event": "diagnostic",
+        "severity": diagnostic.severity.to_string(),
+        "rule": diagnostic.rule.map(|rule| rule.to_string()),
+        "file": diagnostic.file,
+        "line": diagnostic.location.map(|(line, _)| line),
+        "column": diagnostic.location.map(|(_, column)| column),
+        "message": diagnostic.message,
+        "details": diagnostic.details,
+      }));
+      return;
+    }
+
+    // Errors without a rule have always been printed as they are, unless they only consist of a
+    // message which is more helpful along with its location.
+    if self.format == MessageFormat::Human
+      && diagnostic.rule.is_none()
+      && diagnostic.severity == lint::Severity::Error
+      && (diagnostic.details.is_some() || diagnostic.location.is_none())
+    {
+      let message = diagnostic.details.as_ref().unwrap_or(&diagnostic.message);
+      println!("{}", message.red());
+      return;
+    }
+
+    let mut line = String::new();
+    if let Some(file) = &diagnostic.file {
+      line.push_str(&format!("{}:", file.display()));
+      if let Some((l, column)) = diagnostic.location {
+        line.push_str(&format!("{}:{}:", l, column));
+      }
+      line.push(' ');
+    }
+    line.push_str(&format!("{}: {}", diagnostic.severity, diagnostic.message));
+    if let Some(rule) = diagnostic.rule {
+      line.push_str(&format!(" [{}]", rule));
+    }
+
+    self.status(match diagnostic.severity {
+      lint::Severity::Error => line.red(),
+      _ => line.yellow(),
+    });
+  }
+
+  /// Prints a line of human-readable text. Xcode shows escape codes verbatim, so colors are
+  /// dropped in its format.
+  pub fn status(&self, line: ColoredString) {
+    match self.format {
+      MessageFormat::Human => println!("{}", line),
+      MessageFormat::Xcode => println!("{}", line.clear()),
+      MessageFormat::Json => {}
+    }
+  }
+
+  fn event(&self, event: serde_json::Value) {
+    println!("{}", event);
+  }
+}
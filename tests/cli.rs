@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempdir::TempDir;
+
+const STYLESHEET: &str = r#"
+Text {
+  Primary: (light: #151618, dark: #E7E8EA)
+}
+"#;
+
+#[test]
+fn check_swift_file_generated_with_asset_namespace() {
+  let tmp_dir = TempDir::new("check_asset_namespace").expect("Create temp dir failed");
+  let input_file = tmp_dir.path().join("colors.assetstyles");
+  let swift_file = tmp_dir.path().join("Colors.swift");
+  fs::write(&input_file, STYLESHEET).unwrap();
+
+  let output = run(&[
+    "gen-swift",
+    path(&input_file),
+    "-o",
+    path(&swift_file),
+    "--asset-naming",
+    "namespaced",
+    "--asset-namespace",
+    "Generated",
+  ]);
+  assert!(output.status.success());
+  assert!(fs::read_to_string(&swift_file)
+    .unwrap()
+    .contains("\"Generated/Text/Primary\""));
+
+  let check = |namespace: &[&str]| {
+    let mut args = vec![
+      "check",
+      path(&input_file),
+      "--swift",
+      path(&swift_file),
+      "--asset-naming",
+      "namespaced",
+    ];
+    args.extend(namespace);
+    run(&args).status.code()
+  };
+  assert_eq!(check(&["--asset-namespace", "Generated"]), Some(0));
+  assert_eq!(check(&[]), Some(8));
+}
+
+#[test]
+fn diff_with_json_message_format() {
+  let tmp_dir = TempDir::new("diff_json").expect("Create temp dir failed");
+  let old_file = tmp_dir.path().join("old.assetstyles");
+  let input_file = tmp_dir.path().join("colors.assetstyles");
+  fs::write(&old_file, STYLESHEET).unwrap();
+  fs::write(&input_file, STYLESHEET.replace("#151618", "#000000")).unwrap();
+
+  let output = run(&[
+    "diff",
+    path(&old_file),
+    path(&input_file),
+    "--message-format",
+    "json",
+  ]);
+  assert!(output.status.success());
+  let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(json["changed"][0]["path"], "Text.Primary");
+  assert_eq!(json["changed"][0]["light"]["new"], "#000000");
+}
+
+fn run(args: &[&str]) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_xcode-color-assets"))
+    .args(args)
+    .output()
+    .expect("Could not run xcode-color-assets")
+}
+
+fn path(path: &Path) -> &str {
+  path.to_str().unwrap()
+}